@@ -0,0 +1,101 @@
+/*
+    Copyright © 2023, ParallelChain Lab
+    Licensed under the Apache License, Version 2.0: http://www.apache.org/licenses/LICENSE-2.0
+*/
+
+//! Concurrent execution of requests to different endpoints. Compared to awaiting each method of
+//! the [Client] in turn, a [Batch] only takes as long as its slowest request, but every request
+//! is sent even if another one fails.
+
+use pchain_types::rpc::{
+    HighestCommittedBlockResponse, StateRequest, StateResponseV2, ValidatorSetsRequest,
+    ValidatorSetsResponse,
+};
+
+use crate::{error::HttpErrorResponse, Client};
+
+/// [Batch] queues requests to different endpoints, which are sent concurrently by
+/// [execute](Batch::execute). Created by [Client::batch].
+pub struct Batch<'a> {
+    /// `client` denotes the Client which sends the requests.
+    client: &'a Client,
+    /// `state_v2` denotes the queued state request, if any.
+    state_v2: Option<StateRequest>,
+    /// `validator_sets` denotes the queued validator sets request, if any.
+    validator_sets: Option<ValidatorSetsRequest>,
+    /// `highest_committed_block` denotes whether the highest committed block is queried.
+    highest_committed_block: bool,
+}
+
+/// [BatchResults] houses the result of each request in a [Batch]. A field is None if the
+/// corresponding request was not queued.
+pub struct BatchResults {
+    /// `state_v2` denotes the result of the state request.
+    pub state_v2: Option<Result<StateResponseV2, HttpErrorResponse>>,
+    /// `validator_sets` denotes the result of the validator sets request.
+    pub validator_sets: Option<Result<ValidatorSetsResponse, HttpErrorResponse>>,
+    /// `highest_committed_block` denotes the result of the highest committed block request.
+    pub highest_committed_block: Option<Result<HighestCommittedBlockResponse, HttpErrorResponse>>,
+}
+
+impl<'a> Batch<'a> {
+    /// `new` creates an empty Batch for a Client.
+    pub(crate) fn new(client: &'a Client) -> Self {
+        Self {
+            client,
+            state_v2: None,
+            validator_sets: None,
+            highest_committed_block: false,
+        }
+    }
+
+    /// `state_v2` queues a request to query account data from world state using V2 RPC.
+    pub fn state_v2(mut self, request: StateRequest) -> Self {
+        self.state_v2 = Some(request);
+        self
+    }
+
+    /// `validator_sets` queues a request to query previous / current / next validator and
+    /// delegator sets.
+    pub fn validator_sets(mut self, request: ValidatorSetsRequest) -> Self {
+        self.validator_sets = Some(request);
+        self
+    }
+
+    /// `highest_committed_block` queues a request to get the latest block on ParallelChain.
+    pub fn highest_committed_block(mut self) -> Self {
+        self.highest_committed_block = true;
+        self
+    }
+
+    /// `execute` sends the queued requests concurrently and waits for all of them.
+    pub async fn execute(self) -> BatchResults {
+        let client = self.client;
+        let (state_v2, validator_sets, highest_committed_block) = futures::join!(
+            async {
+                match &self.state_v2 {
+                    Some(request) => Some(client.state_v2(request).await),
+                    None => None,
+                }
+            },
+            async {
+                match &self.validator_sets {
+                    Some(request) => Some(client.validator_sets(request).await),
+                    None => None,
+                }
+            },
+            async {
+                match self.highest_committed_block {
+                    true => Some(client.highest_committed_block().await),
+                    false => None,
+                }
+            },
+        );
+
+        BatchResults {
+            state_v2,
+            validator_sets,
+            highest_committed_block,
+        }
+    }
+}
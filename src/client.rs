@@ -8,33 +8,94 @@
 //! correct response.
 
 use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use std::time::Duration;
+#[cfg(feature = "v2")]
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
+
+#[cfg(feature = "v2")]
+use futures::stream::FuturesUnordered;
+use futures::stream::{self, StreamExt};
+#[cfg(any(feature = "v1", feature = "v2"))]
+use pchain_types::rpc::{
+    BlockHeaderRequest, BlockRequest, ReceiptRequest, StateRequest, TransactionRequest, ViewRequest,
+};
+#[cfg(feature = "v1")]
 use pchain_types::{
     blockchain::TransactionV1,
     rpc::{
-        BlockHashByHeightRequest, BlockHashByHeightResponse, BlockHeaderRequest,
-        BlockHeaderResponseV1, BlockHeaderResponseV2, BlockHeightByHashRequest,
-        BlockHeightByHashResponse, BlockRequest, BlockResponseV1, BlockResponseV2, DepositsRequest,
-        DepositsResponse, HighestCommittedBlockResponse, PoolsRequest, PoolsResponse,
-        ReceiptRequest, ReceiptResponseV1, ReceiptResponseV2, StakesRequest, StakesResponse,
-        StateRequest, StateResponseV1, StateResponseV2, SubmitTransactionRequestV1,
-        SubmitTransactionRequestV2, SubmitTransactionResponseV1, SubmitTransactionResponseV2,
-        TransactionPositionRequest, TransactionPositionResponse, TransactionRequest,
-        TransactionResponseV1, TransactionResponseV2, TransactionV1OrV2, ValidatorSetsRequest,
-        ValidatorSetsResponse, ViewRequest, ViewResponseV1, ViewResponseV2,
+        BlockHeaderResponseV1, BlockResponseV1, ReceiptResponseV1, StateResponseV1,
+        SubmitTransactionRequestV1, SubmitTransactionResponseV1, TransactionResponseV1,
+        ViewResponseV1,
+    },
+};
+#[cfg(feature = "v2")]
+use pchain_types::{
+    blockchain::{BlockHeaderV2, CommandReceiptV2},
+    cryptography::PublicAddress,
+    rpc::{
+        Account, BlockHeaderResponseV2, BlockResponseV2, ReceiptResponseV2, StateResponseV2,
+        SubmitTransactionRequestV2, SubmitTransactionResponseV2, TransactionResponseV2,
+        TransactionV1OrV2, ViewResponseV2,
+    },
+};
+use pchain_types::{
+    cryptography::CryptoHash,
+    rpc::{
+        BlockHashByHeightRequest, BlockHashByHeightResponse, BlockHeightByHashRequest,
+        BlockHeightByHashResponse, DepositsRequest, DepositsResponse,
+        HighestCommittedBlockResponse, PoolsRequest, PoolsResponse, StakesRequest, StakesResponse,
+        TransactionPositionRequest, TransactionPositionResponse, ValidatorSetsRequest,
+        ValidatorSetsResponse,
     },
     serialization::{Deserializable, Serializable},
 };
 
+#[cfg(feature = "v2")]
+use crate::{
+    batch::Batch,
+    types::{ChainInfo, ContractInfo, GasParams, TransactionsPage},
+    utils,
+};
 use crate::{
     error::{self as PChainClientError, HttpErrorResponse},
-    networking::{NetworkProvider, Networking},
+    middleware::RequestMiddleware,
+    networking::{Encoding, NetworkProvider, Networking, RawResponse},
+    runtime,
+    types::PreparedRequest,
+    utils::ToBase64UrlDisplay,
 };
 
+/// Environment variable read by [Client::from_env] for the RPC base URL.
+const ENV_RPC_URL: &str = "PCHAIN_RPC_URL";
+/// Environment variable read by [Client::from_env] for the connect timeout in seconds.
+const ENV_CONNECT_TIMEOUT_SECS: &str = "PCHAIN_CONNECT_TIMEOUT_SECS";
+
+/// Maximum number of concurrent requests sent by a single call of the methods which fetch
+/// several items, e.g. [Client::block_hashes_by_heights].
+const FETCH_CONCURRENCY_LIMIT: usize = 8;
+
+/// `env_var` reads an environment variable. Returns None if the variable is not set, and an
+/// error if its value is not valid unicode.
+fn env_var(name: &str) -> Result<Option<String>, String> {
+    match std::env::var(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(e) => Err(format!("Environment variable {} is invalid: {}", name, e)),
+    }
+}
+
 /// [Client] sets up the networking with methods corresponding to both fullnode RPC V1 and
 /// fullnode RPC V2.
 pub struct Client {
     /// `networking` denotes the instance of reqwest::Client.
     networking: Networking,
+    /// `explorer_base_url` denotes the URL which transaction hashes are appended to in order
+    /// to link to a block explorer, if set.
+    explorer_base_url: Option<String>,
 }
 
 #[async_trait]
@@ -60,9 +121,71 @@ impl Client {
     pub fn new(rpc_base_url: &str) -> Self {
         Self {
             networking: Networking::new(String::from(rpc_base_url)),
+            explorer_base_url: None,
         }
     }
 
+    /// `with_base_path` creates a new instance of a pchain_client given a network provider
+    /// which serves the RPC endpoints under a path prefix.
+    /// # Arguments
+    /// * `rpc_base_url` - URL of the network provider, e.g. `https://gateway`
+    /// * `base_path` - path prefix of the RPC endpoints, e.g. `pchain/mainnet`. Leading and
+    ///   trailing slashes are ignored.
+    ///
+    /// # Return
+    /// Error message if the base path is invalid.
+    ///
+    pub fn with_base_path(rpc_base_url: &str, base_path: &str) -> Result<Self, String> {
+        let mut client = Self::new(rpc_base_url);
+        client.networking.set_base_path(base_path)?;
+        Ok(client)
+    }
+
+    /// `from_env` creates a new instance of a pchain_client configured from environment
+    /// variables.
+    /// # Environment Variables
+    /// * `PCHAIN_RPC_URL` - base URL of Parallelchain RPC endpoints (required)
+    /// * `PCHAIN_CONNECT_TIMEOUT_SECS` - connect timeout in seconds (optional)
+    ///
+    /// # Return
+    /// Error message naming the variable which is missing or invalid.
+    ///
+    pub fn from_env() -> Result<Self, String> {
+        let rpc_base_url = env_var(ENV_RPC_URL)?
+            .ok_or_else(|| format!("Environment variable {} is missing.", ENV_RPC_URL))?;
+        if rpc_base_url.is_empty() {
+            return Err(format!(
+                "Environment variable {} is invalid: empty value",
+                ENV_RPC_URL
+            ));
+        }
+        reqwest::Url::parse(&rpc_base_url)
+            .map_err(|e| format!("Environment variable {} is invalid: {}", ENV_RPC_URL, e))?;
+        let mut client = Self::new(&rpc_base_url);
+
+        if let Some(secs) = env_var(ENV_CONNECT_TIMEOUT_SECS)? {
+            let secs = secs.parse::<u64>().map_err(|e| {
+                format!(
+                    "Environment variable {} is invalid: {}",
+                    ENV_CONNECT_TIMEOUT_SECS, e
+                )
+            })?;
+            client.set_connect_timeout(Duration::from_secs(secs));
+        }
+
+        Ok(client)
+    }
+
+    /// `set_connect_timeout` sets the timeout for establishing a connection to the network
+    /// provider. Defaults to 10 secs. The provider URL and other settings are preserved, but
+    /// the underlying HTTP client is rebuilt and hence its connection pool is reset.
+    /// # Arguments
+    /// * `timeout` - connect timeout
+    ///
+    pub fn set_connect_timeout(&mut self, timeout: Duration) {
+        self.networking.set_connect_timeout(timeout);
+    }
+
     /// `highest_committed_block` sends a request to get the latest block on ParallelChain.
     pub async fn highest_committed_block(
         &self,
@@ -73,34 +196,331 @@ impl Client {
             .await
             .map_err(PChainClientError::new)?;
 
-        HighestCommittedBlockResponse::deserialize(&raw_bytes)
-            .map_err(|e| PChainClientError::new(e.to_string()))
+        self.decode(&raw_bytes)
+    }
+
+    /// `set_danger_accept_invalid_certs` sets whether invalid TLS certificates of the network
+    /// provider, e.g. self-signed ones, are accepted. Defaults to false.
+    ///
+    /// **DANGER**: enabling this exposes the Client to man-in-the-middle attacks. It is meant
+    /// only for testing against a locally run node, and a warning is logged when it is enabled.
+    /// The flag is passed to reqwest as is, and is not tested against a server with a
+    /// self-signed certificate.
+    /// # Arguments
+    /// * `accept_invalid_certs` - true to accept invalid certificates
+    ///
+    pub fn set_danger_accept_invalid_certs(&mut self, accept_invalid_certs: bool) {
+        self.networking
+            .set_danger_accept_invalid_certs(accept_invalid_certs);
+    }
+
+    /// `set_max_in_flight_requests` limits the total number of concurrent requests sent by the
+    /// Client, providing backpressure when many requests are issued at once. Requests beyond
+    /// the limit wait until an earlier request completes. A [RawResponse] returned by
+    /// [raw_response](Client::raw_response) holds its slot until it is dropped. Unbounded by
+    /// default.
+    /// # Arguments
+    /// * `max` - maximum number of concurrent requests, or None for no limit
+    ///
+    /// # Return
+    /// Error message if `max` is zero.
+    ///
+    pub fn set_max_in_flight_requests(&mut self, max: Option<usize>) -> Result<(), String> {
+        self.networking.set_max_in_flight_requests(max)
+    }
+
+    /// `set_pool_max_idle_per_host` sets the maximum number of idle connections kept open per
+    /// host, so that one host cannot monopolize the connection pool when several providers are
+    /// used, e.g. by `broadcast_transaction_v2`. Unbounded by default. reqwest does not limit
+    /// active connections per host, so use
+    /// [set_max_in_flight_requests](Client::set_max_in_flight_requests) to bound the total
+    /// number of concurrent requests across all hosts.
+    /// # Arguments
+    /// * `max` - maximum number of idle connections per host
+    ///
+    pub fn set_pool_max_idle_per_host(&mut self, max: usize) {
+        self.networking.set_pool_max_idle_per_host(max);
+    }
+
+    /// `set_max_redirects` sets the maximum number of redirects followed for a request, 10 by
+    /// default. 0 refuses every redirect. Requests redirected more times fail with a "Too many
+    /// redirects" error. Redirects from HTTPS to HTTP are refused regardless.
+    /// # Arguments
+    /// * `max` - maximum number of redirects
+    ///
+    pub fn set_max_redirects(&mut self, max: usize) {
+        self.networking.set_max_redirects(max);
+    }
+
+    /// `set_explorer_base_url` sets the URL of the transaction pages of a block explorer, used
+    /// by [explorer_url](Client::explorer_url). The link to a transaction is this URL, a slash,
+    /// and the Base64URL transaction hash, i.e. `{explorer_base_url}/{hash}`. Set a different
+    /// URL for each network, including whatever path the explorer serves transactions under.
+    /// # Arguments
+    /// * `explorer_base_url` - URL of the transaction pages, e.g. `https://explorer/tx`.
+    ///   Trailing slashes are ignored.
+    ///
+    pub fn set_explorer_base_url(&mut self, explorer_base_url: &str) {
+        self.explorer_base_url = Some(explorer_base_url.trim_end_matches('/').to_string());
+    }
+
+    /// `explorer_url` composes the block explorer link to a transaction as documented in
+    /// [set_explorer_base_url](Client::set_explorer_base_url).
+    /// # Arguments
+    /// * `transaction_hash` - hash of the transaction
+    ///
+    /// # Return
+    /// None if the explorer base URL is not set.
+    ///
+    pub fn explorer_url(&self, transaction_hash: &CryptoHash) -> Option<String> {
+        self.explorer_base_url
+            .as_ref()
+            .map(|base_url| format!("{}/{}", base_url, transaction_hash.to_base64url()))
+    }
+
+    /// `set_fail_fast` sets whether requests fail immediately, instead of waiting for a
+    /// timeout, while the last health check by
+    /// [is_provider_up](NetworkProvider::is_provider_up) found the provider down. Requests are
+    /// sent again once a health check finds the provider up, or the provider is changed.
+    /// Disabled by default.
+    /// # Arguments
+    /// * `fail_fast` - true to fail fast
+    ///
+    pub fn set_fail_fast(&mut self, fail_fast: bool) {
+        self.networking.set_fail_fast(fail_fast);
+    }
+
+    /// `add_middleware` adds a [RequestMiddleware] which can modify every subsequent request,
+    /// e.g. to add headers. Middlewares are applied in the order in which they are added.
+    /// # Arguments
+    /// * `middleware` - middleware to be added
+    ///
+    pub fn add_middleware<M: RequestMiddleware + 'static>(&mut self, middleware: M) {
+        self.networking.add_middleware(Box::new(middleware));
+    }
+
+    /// `set_encoding` sets the wire format of request and response bodies. Defaults to
+    /// [Encoding::Borsh], which is currently the only encoding supported by the endpoints.
+    /// # Arguments
+    /// * `encoding` - wire format of request and response bodies
+    ///
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.networking.set_encoding(encoding);
+    }
+
+    /// `set_retain_error_body` sets whether the Client retains the verbatim body of the last
+    /// error response (at most 64 KiB). Disabled by default, since error bodies may contain
+    /// sensitive data. The retained body is cleared on the next successful request.
+    /// # Arguments
+    /// * `retain` - true to retain the body of the last error response
+    ///
+    pub fn set_retain_error_body(&mut self, retain: bool) {
+        self.networking.set_retain_error_body(retain);
+    }
+
+    /// `last_error_body` returns the verbatim body of the last error response, if retaining
+    /// it is enabled by [set_retain_error_body](Client::set_retain_error_body).
+    pub fn last_error_body(&self) -> Option<bytes::Bytes> {
+        self.networking.last_error_body()
+    }
+
+    /// `last_retry_after` returns the delay which the network provider asked for in the
+    /// Retry-After header of the last response, if it was a 503 Service Unavailable response.
+    /// Retry logic can wait this long before sending the next request. Cleared by the next
+    /// response.
+    pub fn last_retry_after(&self) -> Option<Duration> {
+        self.networking.last_retry_after()
+    }
+
+    /// `ping` sends the health check request to the network provider and measures its round
+    /// trip. DNS resolution and TLS setup are included unless a pooled connection to the
+    /// provider is reused.
+    ///
+    /// # Return
+    /// Round trip time of the health check request.
+    ///
+    pub async fn ping(&self) -> Result<Duration, HttpErrorResponse> {
+        let provider = self.networking.get_provider();
+        self.networking
+            .ping(&provider)
+            .await
+            .map_err(PChainClientError::new)
+    }
+
+    /// `compare_providers` pings network providers one after another with the settings of this
+    /// Client, e.g. to choose the fastest one from a pool. Each provider is pinged twice and the
+    /// second round trip is kept, so that connection setup is excluded where the connection is
+    /// pooled.
+    /// # Arguments
+    /// * `rpc_base_urls` - base URLs of the network providers
+    ///
+    /// # Return
+    /// The providers with their round trip times, fastest first. Unreachable providers come
+    /// last, with their errors.
+    ///
+    pub async fn compare_providers(
+        &self,
+        rpc_base_urls: &[&str],
+    ) -> Vec<(String, Result<Duration, HttpErrorResponse>)> {
+        let mut results = Vec::with_capacity(rpc_base_urls.len());
+        for rpc_base_url in rpc_base_urls {
+            let latency = match self.networking.ping(rpc_base_url).await {
+                Ok(_) => self.networking.ping(rpc_base_url).await,
+                Err(e) => Err(e),
+            };
+            results.push((
+                rpc_base_url.to_string(),
+                latency.map_err(PChainClientError::new),
+            ));
+        }
+
+        results.sort_by_key(|(_, latency)| match latency {
+            Ok(latency) => (false, *latency),
+            Err(_) => (true, Duration::default()),
+        });
+        results
+    }
+
+    /// `encode` serializes a request according to the encoding of the Client.
+    fn encode<I: Serializable>(&self, input: &I) -> Result<bytes::Bytes, HttpErrorResponse> {
+        match self.networking.get_encoding() {
+            Encoding::Borsh => Ok(<I as Serializable>::serialize(input).into()),
+            Encoding::Json => Err(String::from("JSON encoding is not supported yet.")),
+        }
+    }
+
+    /// `prepare` serializes a request once according to the encoding of the Client, so that it
+    /// can be sent repeatedly without being serialized again, e.g. by
+    /// [state_v2_prepared](Client::state_v2_prepared).
+    /// # Arguments
+    /// * `request` - request to be serialized
+    ///
+    pub fn prepare<I: Serializable>(
+        &self,
+        request: &I,
+    ) -> Result<PreparedRequest<I>, HttpErrorResponse> {
+        Ok(PreparedRequest::new(
+            self.encode(request)?,
+            self.networking.get_encoding(),
+        ))
+    }
+
+    /// `post_prepared` is [post_request](Client::post_request) with a prepared input request.
+    #[cfg(feature = "v2")]
+    async fn post_prepared<I, O: Deserializable>(
+        &self,
+        prepared: &PreparedRequest<I>,
+        endpoint_path: &str,
+    ) -> Result<O, HttpErrorResponse> {
+        if prepared.encoding != self.networking.get_encoding() {
+            return Err(String::from(
+                "Prepared request does not match the encoding of the Client.",
+            ));
+        }
+        self.post_encoded(prepared.data.clone(), endpoint_path, HeaderMap::new())
+            .await
+    }
+
+    /// `decode` deserializes a response according to the encoding of the Client.
+    fn decode<O: Deserializable>(&self, raw_bytes: &[u8]) -> Result<O, HttpErrorResponse> {
+        match self.networking.get_encoding() {
+            Encoding::Borsh => <O as Deserializable>::deserialize(raw_bytes)
+                .map_err(|e| PChainClientError::new(e.to_string())),
+            Encoding::Json => Err(String::from("JSON encoding is not supported yet.")),
+        }
     }
 
     /// `post_request` defines the generic implementation of POST requests for RPC:
-    /// 1. serialize the input request.
+    /// 1. serialize the input request according to the encoding.
     /// 2. send a POST request to the network provider for the Client.
-    /// 3. deserialize the output response.
+    /// 3. deserialize the output response according to the encoding.
     async fn post_request<I: Serializable, O: Deserializable>(
         &self,
         input: &I,
         endpoint_path: &str,
     ) -> Result<O, HttpErrorResponse> {
-        let data = <I as Serializable>::serialize(input);
+        self.post_request_with_headers(input, endpoint_path, HeaderMap::new())
+            .await
+    }
+
+    /// `post_request_with_headers` is [post_request](Client::post_request) with extra headers
+    /// for this request only.
+    async fn post_request_with_headers<I: Serializable, O: Deserializable>(
+        &self,
+        input: &I,
+        endpoint_path: &str,
+        headers: HeaderMap,
+    ) -> Result<O, HttpErrorResponse> {
+        let data = self.encode(input)?;
+        self.post_encoded(data, endpoint_path, headers).await
+    }
 
+    /// `post_encoded` is [post_request](Client::post_request) with an input request which is
+    /// already serialized.
+    async fn post_encoded<O: Deserializable>(
+        &self,
+        data: bytes::Bytes,
+        endpoint_path: &str,
+        headers: HeaderMap,
+    ) -> Result<O, HttpErrorResponse> {
         let raw_bytes = self
             .networking
-            .post_response(endpoint_path, data)
+            .post_response_with_headers(endpoint_path, data, headers)
             .await
             .map_err(PChainClientError::new)?;
 
-        let response = <O as Deserializable>::deserialize(&raw_bytes)
-            .map_err(|e| PChainClientError::new(e.to_string()))?;
-        Ok(response)
+        self.decode(&raw_bytes)
+    }
+
+    /// `post_request_with_timeout` sends a POST request to an endpoint like the other methods of
+    /// the Client, but with an optional timeout for this call only. The timeout is driven by the
+    /// tokio 1 timer, so the call must run within a tokio 1 runtime like every other request.
+    /// # Arguments
+    /// * `input` - request to be serialized according to the encoding of the Client
+    /// * `endpoint_path` - path of the endpoint, e.g. `view/v2`
+    /// * `timeout` - maximum duration of the call, or None to wait indefinitely
+    ///
+    pub async fn post_request_with_timeout<I: Serializable, O: Deserializable>(
+        &self,
+        input: &I,
+        endpoint_path: &str,
+        timeout: Option<Duration>,
+    ) -> Result<O, HttpErrorResponse> {
+        match timeout {
+            Some(timeout) => runtime::timeout(timeout, self.post_request(input, endpoint_path))
+                .await
+                .map_err(|_| String::from("Request timed out."))?,
+            None => self.post_request(input, endpoint_path).await,
+        }
+    }
+
+    /// `raw_response` sends a POST request to an endpoint and returns the full HTTP response,
+    /// e.g. to inspect response headers. Neither the status nor the body is checked, the caller
+    /// reads and deserializes the body. If the number of in-flight requests is limited, the
+    /// returned [RawResponse] holds its slot until it is dropped.
+    /// # Arguments
+    /// * `input` - request to be serialized according to the encoding of the Client
+    /// * `endpoint_path` - path of the endpoint, e.g. `block/v2`
+    ///
+    pub async fn raw_response<I: Serializable>(
+        &self,
+        input: &I,
+        endpoint_path: &str,
+    ) -> Result<RawResponse, HttpErrorResponse> {
+        let data = self.encode(input)?;
+
+        self.networking
+            .post_raw_response(endpoint_path, data)
+            .await
+            .map_err(PChainClientError::new)
     }
 
     /// `validator_sets` sends a request to query previous / current / next validator
     /// and delegator sets.
+    ///
+    /// The sets are relative to the current epoch of the provider. The request has no height
+    /// or epoch qualifier, and block headers do not record validator sets, so the sets of an
+    /// arbitrary past epoch cannot be queried or reconstructed through RPC.
     pub async fn validator_sets(
         &self,
         request: &ValidatorSetsRequest,
@@ -150,6 +570,26 @@ impl Client {
         self.post_request(request, "block_hash_by_height").await
     }
 
+    /// `block_hashes_by_heights` sends requests to get block hashes by specified block heights
+    /// concurrently, at most 8 at a time. The total number of concurrent requests of the Client
+    /// is further bounded by [set_max_in_flight_requests](Client::set_max_in_flight_requests),
+    /// if set.
+    ///
+    /// # Return
+    /// Results in the same order as `heights`.
+    pub async fn block_hashes_by_heights(
+        &self,
+        heights: &[u64],
+    ) -> Vec<Result<BlockHashByHeightResponse, HttpErrorResponse>> {
+        stream::iter(heights.iter().map(|&block_height| async move {
+            self.block_hash_by_height(&BlockHashByHeightRequest { block_height })
+                .await
+        }))
+        .buffered(FETCH_CONCURRENCY_LIMIT)
+        .collect()
+        .await
+    }
+
     /// `transaction_position` sends a request to get transaction position in block by
     /// specified tx hash.
     pub async fn transaction_position(
@@ -160,6 +600,7 @@ impl Client {
     }
 
     /// `submit_transaction_v1` sends a request to submit a transaction using V1 RPC.
+    #[cfg(feature = "v1")]
     pub async fn submit_transaction_v1(
         &self,
         tx: &TransactionV1,
@@ -171,7 +612,26 @@ impl Client {
         self.post_request(&request, "submit_transaction").await
     }
 
+    /// `submit_transaction_v1_via_v2` sends a request to submit a transaction in the V1 format
+    /// using V2 RPC, which accepts both formats. Use it for nodes which still expect V1
+    /// transactions but serve V2 RPC. Nodes which serve only V1 RPC require
+    /// [submit_transaction_v1](Client::submit_transaction_v1) instead. The opposite combination,
+    /// a V2 transaction through V1 RPC, is not supported by any node and is ruled out by the
+    /// argument types.
+    /// # Arguments
+    /// * `tx` - transaction in the V1 format to be submitted
+    ///
+    #[cfg(all(feature = "v1", feature = "v2"))]
+    pub async fn submit_transaction_v1_via_v2(
+        &self,
+        tx: &TransactionV1,
+    ) -> Result<SubmitTransactionResponseV2, HttpErrorResponse> {
+        self.submit_transaction_v2(&TransactionV1OrV2::V1(tx.clone()))
+            .await
+    }
+
     /// `submit_transaction_v2` sends a request to submit a transaction using V2 RPC.
+    #[cfg(feature = "v2")]
     pub async fn submit_transaction_v2(
         &self,
         tx: &TransactionV1OrV2,
@@ -183,7 +643,115 @@ impl Client {
         self.post_request(&request, "submit_transaction/v2").await
     }
 
+    /// `submit_transaction_v2_with_link` sends a request to submit a transaction using V2 RPC,
+    /// and composes the block explorer link to the transaction from its hash as documented in
+    /// [set_explorer_base_url](Client::set_explorer_base_url).
+    /// # Arguments
+    /// * `tx` - transaction to be submitted
+    ///
+    /// # Return
+    /// The response and the explorer link. Error message without submitting the transaction
+    /// if the explorer base URL is not set.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn submit_transaction_v2_with_link(
+        &self,
+        tx: &TransactionV1OrV2,
+    ) -> Result<(SubmitTransactionResponseV2, String), HttpErrorResponse> {
+        let transaction_hash = match tx {
+            TransactionV1OrV2::V1(tx) => &tx.hash,
+            TransactionV1OrV2::V2(tx) => &tx.hash,
+        };
+        let link = self
+            .explorer_url(transaction_hash)
+            .ok_or_else(|| String::from("Explorer base URL is not set."))?;
+
+        let response = self.submit_transaction_v2(tx).await?;
+        Ok((response, link))
+    }
+
+    /// `submit_transaction_v2_with_headers` sends a request to submit a transaction using V2
+    /// RPC, with extra headers for this request only, e.g. a correlation ID. A header set here
+    /// overwrites the header of the same name set by the middlewares of the Client.
+    /// # Arguments
+    /// * `tx` - transaction to be submitted
+    /// * `headers` - headers of this request only
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn submit_transaction_v2_with_headers(
+        &self,
+        tx: &TransactionV1OrV2,
+        headers: HeaderMap,
+    ) -> Result<SubmitTransactionResponseV2, HttpErrorResponse> {
+        let request = SubmitTransactionRequestV2 {
+            transaction: tx.clone(),
+        };
+
+        self.post_request_with_headers(&request, "submit_transaction/v2", headers)
+            .await
+    }
+
+    /// `broadcast_transaction_v2` submits a transaction to several network providers
+    /// concurrently using V2 RPC, and returns the first successful acceptance. A response which
+    /// rejects the transaction, e.g. because the nonce is too low or the mempool is full, counts
+    /// as a failure, so a slower provider may still accept the transaction. The transaction
+    /// hash is identical across providers, so a duplicate acceptance by another provider is
+    /// harmless. The base path of the Client applies to every provider.
+    /// # Arguments
+    /// * `tx` - transaction to be submitted
+    /// * `urls` - base URLs of the network providers
+    ///
+    /// # Return
+    /// Error messages of all providers if none of them accepts the transaction.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn broadcast_transaction_v2(
+        &self,
+        tx: &TransactionV1OrV2,
+        urls: &[String],
+    ) -> Result<SubmitTransactionResponseV2, HttpErrorResponse> {
+        let request = SubmitTransactionRequestV2 {
+            transaction: tx.clone(),
+        };
+        let data = self.encode(&request)?;
+
+        let mut submissions: FuturesUnordered<_> = urls
+            .iter()
+            .map(|url| {
+                let data = data.clone();
+                async move {
+                    let result: Result<SubmitTransactionResponseV2, HttpErrorResponse> = match self
+                        .networking
+                        .post_response_to(url, "submit_transaction/v2", data, HeaderMap::new())
+                        .await
+                    {
+                        Ok(raw_bytes) => self.decode(&raw_bytes),
+                        Err(e) => Err(PChainClientError::new(e)),
+                    };
+                    (url, result)
+                }
+            })
+            .collect();
+
+        let mut errors = Vec::new();
+        while let Some((url, result)) = submissions.next().await {
+            match result {
+                Ok(response) => match &response.error {
+                    Some(error) => errors.push(format!("{}: rejected with {:?}", url, error)),
+                    None => return Ok(response),
+                },
+                Err(e) => errors.push(format!("{}: {}", url, e)),
+            }
+        }
+
+        Err(format!(
+            "Transaction is not accepted by any provider. {}",
+            errors.join("; ")
+        ))
+    }
+
     /// `state_v1` sends a request to query account data from world state using V1 RPC.
+    #[cfg(feature = "v1")]
     pub async fn state_v1(
         &self,
         request: &StateRequest,
@@ -192,6 +760,7 @@ impl Client {
     }
 
     /// `state_v2` sends a request to query account data from world state using V2 RPC.
+    #[cfg(feature = "v2")]
     pub async fn state_v2(
         &self,
         request: &StateRequest,
@@ -199,7 +768,19 @@ impl Client {
         self.post_request(request, "state/v2").await
     }
 
+    /// `state_v2_prepared` sends a request to query account data from world state using V2
+    /// RPC, with a request prepared by [prepare](Client::prepare). This skips serializing the
+    /// request, e.g. when polling the same request at high frequency.
+    #[cfg(feature = "v2")]
+    pub async fn state_v2_prepared(
+        &self,
+        prepared: &PreparedRequest<StateRequest>,
+    ) -> Result<StateResponseV2, HttpErrorResponse> {
+        self.post_prepared(prepared, "state/v2").await
+    }
+
     /// `view_v1` sends a request to execute a contract view call using V1 RPC.
+    #[cfg(feature = "v1")]
     pub async fn view_v1(
         &self,
         request: &ViewRequest,
@@ -208,6 +789,11 @@ impl Client {
     }
 
     /// `view_v2` sends a request to execute a contract view call using V2 RPC.
+    ///
+    /// A view call executes a contract method without committing, but it takes a contract
+    /// method call rather than a transaction. The RPC has no simulate or dry-run endpoint, so a
+    /// transaction cannot be simulated, e.g. to estimate its gas, before it is submitted.
+    #[cfg(feature = "v2")]
     pub async fn view_v2(
         &self,
         request: &ViewRequest,
@@ -217,6 +803,7 @@ impl Client {
 
     /// `block_v1` sends a request to get full block data starting from specified
     /// block hash using V1 RPC.
+    #[cfg(feature = "v1")]
     pub async fn block_v1(
         &self,
         request: &BlockRequest,
@@ -226,6 +813,7 @@ impl Client {
 
     /// `block_v2` sends a request to get full block data starting from specified
     /// block hash using V2 RPC.
+    #[cfg(feature = "v2")]
     pub async fn block_v2(
         &self,
         request: &BlockRequest,
@@ -233,8 +821,49 @@ impl Client {
         self.post_request(request, "block/v2").await
     }
 
+    /// `blocks_by_heights_v2` sends requests to get full block data by specified block heights
+    /// concurrently using V2 RPC. Each height is resolved to a block hash before its block is
+    /// requested, so the heights need not be contiguous. At most 8 heights are fetched at a
+    /// time. The total number of concurrent requests of the Client is further bounded by
+    /// [set_max_in_flight_requests](Client::set_max_in_flight_requests), if set.
+    ///
+    /// # Return
+    /// Results in the same order as `heights`.
+    #[cfg(feature = "v2")]
+    pub async fn blocks_by_heights_v2(
+        &self,
+        heights: &[u64],
+    ) -> Vec<Result<BlockResponseV2, HttpErrorResponse>> {
+        stream::iter(heights.iter().map(|&block_height| async move {
+            let block_hash = self
+                .block_hash_by_height(&BlockHashByHeightRequest { block_height })
+                .await?
+                .block_hash
+                .ok_or_else(|| format!("Block hash at height {} is not found.", block_height))?;
+
+            self.block_v2(&BlockRequest { block_hash }).await
+        }))
+        .buffered(FETCH_CONCURRENCY_LIMIT)
+        .collect()
+        .await
+    }
+
+    /// `block_both` sends requests to get full block data starting from specified block hash
+    /// using both V1 RPC and V2 RPC concurrently.
+    ///
+    /// This is a diagnostic aid for verifying that both endpoints are consistent during the
+    /// migration from V1 to V2, not a permanent API.
+    #[cfg(all(feature = "v1", feature = "v2"))]
+    pub async fn block_both(
+        &self,
+        request: &BlockRequest,
+    ) -> Result<(BlockResponseV1, BlockResponseV2), HttpErrorResponse> {
+        futures::try_join!(self.block_v1(request), self.block_v2(request))
+    }
+
     /// `block_header_v1` sends a request to get block header starting from specified
     /// block hash using V1 RPC.
+    #[cfg(feature = "v1")]
     pub async fn block_header_v1(
         &self,
         request: &BlockHeaderRequest,
@@ -244,6 +873,7 @@ impl Client {
 
     /// `block_header_v2` sends a request to get block header starting from specified
     /// block hash using V2 RPC.
+    #[cfg(feature = "v2")]
     pub async fn block_header_v2(
         &self,
         request: &BlockHeaderRequest,
@@ -253,6 +883,7 @@ impl Client {
 
     /// `transaction_v1` sends a request to get transaction by specified tx hash using
     /// V1 RPC.
+    #[cfg(feature = "v1")]
     pub async fn transaction_v1(
         &self,
         request: &TransactionRequest,
@@ -262,6 +893,7 @@ impl Client {
 
     /// `transaction_v2` sends a request to get transaction by specified tx hash using
     /// V2 RPC.
+    #[cfg(feature = "v2")]
     pub async fn transaction_v2(
         &self,
         request: &TransactionRequest,
@@ -269,8 +901,48 @@ impl Client {
         self.post_request(request, "transaction/v2").await
     }
 
+    /// `transaction_v2_opt` sends a request to get transaction by specified tx hash using
+    /// V2 RPC. The provider signals a transaction which is not found with a successful response
+    /// whose `transaction` field is None, rather than with an error status.
+    ///
+    /// # Return
+    /// None if the transaction is not found. Errors only on request or response failures.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn transaction_v2_opt(
+        &self,
+        request: &TransactionRequest,
+    ) -> Result<Option<TransactionResponseV2>, HttpErrorResponse> {
+        let response = self.transaction_v2(request).await?;
+        Ok(response.transaction.is_some().then_some(response))
+    }
+
+    /// `transaction_any` sends a request to get transaction by specified tx hash without
+    /// knowing whether it is a V1 or V2 transaction. V2 RPC is tried first, and V1 RPC is tried
+    /// if V2 RPC fails or does not find the transaction, which costs an extra round trip.
+    #[cfg(all(feature = "v1", feature = "v2"))]
+    pub async fn transaction_any(
+        &self,
+        request: &TransactionRequest,
+    ) -> Result<TransactionV1OrV2, HttpErrorResponse> {
+        if let Ok(TransactionResponseV2 {
+            transaction: Some(transaction),
+            ..
+        }) = self.transaction_v2(request).await
+        {
+            return Ok(transaction);
+        }
+
+        self.transaction_v1(request)
+            .await?
+            .transaction
+            .map(TransactionV1OrV2::V1)
+            .ok_or_else(|| String::from("Transaction is not found."))
+    }
+
     /// `receipt_v1` sends a request to get receipt with transaction, block hash and
     /// position by specified tx hash using V1 RPC.
+    #[cfg(feature = "v1")]
     pub async fn receipt_v1(
         &self,
         request: &ReceiptRequest,
@@ -280,10 +952,646 @@ impl Client {
 
     /// `receipt_v2` sends a request to get receipt with transaction, block hash and
     /// position by specified tx hash using V2 RPC.
+    #[cfg(feature = "v2")]
     pub async fn receipt_v2(
         &self,
         request: &ReceiptRequest,
     ) -> Result<ReceiptResponseV2, HttpErrorResponse> {
         self.post_request(request, "receipt/v2").await
     }
+
+    /// `receipt_v2_opt` sends a request to get receipt by specified tx hash using V2 RPC. The
+    /// provider signals a receipt which is not found with a successful response whose `receipt`
+    /// field is None, rather than with an error status.
+    ///
+    /// # Return
+    /// None if the receipt is not found. Errors only on request or response failures.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn receipt_v2_opt(
+        &self,
+        request: &ReceiptRequest,
+    ) -> Result<Option<ReceiptResponseV2>, HttpErrorResponse> {
+        let response = self.receipt_v2(request).await?;
+        Ok(response.receipt.is_some().then_some(response))
+    }
+
+    /// `receipt_matches_topic_v2` sends a request to get the receipt of a transaction using V2
+    /// RPC, and checks whether any log emitted by its Call commands has a topic.
+    ///
+    /// Receipts carry no bloom filter or topic index, so there is no cheap pre-check: the
+    /// whole receipt is downloaded and the topic is compared byte by byte against every log,
+    /// without decoding log values. The cost therefore grows with the size of the receipt.
+    /// # Arguments
+    /// * `transaction_hash` - hash of the transaction
+    /// * `topic` - topic of the log to be matched
+    ///
+    /// # Return
+    /// Error message if the receipt is not found.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn receipt_matches_topic_v2(
+        &self,
+        transaction_hash: CryptoHash,
+        topic: &[u8],
+    ) -> Result<bool, HttpErrorResponse> {
+        let receipt = self
+            .receipt_v2(&ReceiptRequest { transaction_hash })
+            .await?
+            .receipt
+            .ok_or_else(|| String::from("Receipt is not found."))?;
+
+        Ok(receipt
+            .command_receipts
+            .iter()
+            .any(|command_receipt| match command_receipt {
+                CommandReceiptV2::Call(call_receipt) => {
+                    call_receipt.logs.iter().any(|log| log.topic == topic)
+                }
+                _ => false,
+            }))
+    }
+
+    /// `batch` creates a [Batch] which sends requests to different endpoints concurrently.
+    #[cfg(feature = "v2")]
+    pub fn batch(&self) -> Batch<'_> {
+        Batch::new(self)
+    }
+
+    /// `chain_info` sends requests to get the chain ID and the hash of the genesis block, which
+    /// identify the chain served by the network provider.
+    #[cfg(feature = "v2")]
+    pub async fn chain_info(&self) -> Result<ChainInfo, HttpErrorResponse> {
+        let genesis_hash = self
+            .block_hash_by_height(&BlockHashByHeightRequest { block_height: 0 })
+            .await?
+            .block_hash
+            .ok_or_else(|| String::from("Genesis block is not found."))?;
+
+        let header = self
+            .block_header_v2(&BlockHeaderRequest {
+                block_hash: genesis_hash,
+            })
+            .await?
+            .block_header
+            .ok_or_else(|| String::from("Genesis block header is not found."))?;
+
+        Ok(ChainInfo {
+            chain_id: header.chain_id,
+            genesis_hash,
+        })
+    }
+
+    /// `current_block_time` sends requests to get the timestamp of the highest committed block
+    /// using V2 RPC.
+    ///
+    /// # Return
+    /// Timestamp in seconds since the Unix epoch.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn current_block_time(&self) -> Result<u64, HttpErrorResponse> {
+        let header = self.highest_committed_block_header_v2().await?;
+        Ok(u64::from(header.timestamp))
+    }
+
+    /// `gas_params_v2` sends requests to get the gas parameters of the highest committed block
+    /// using V2 RPC.
+    #[cfg(feature = "v2")]
+    pub async fn gas_params_v2(&self) -> Result<GasParams, HttpErrorResponse> {
+        let header = self.highest_committed_block_header_v2().await?;
+
+        Ok(GasParams {
+            block_hash: header.hash,
+            base_fee_per_gas: header.base_fee_per_gas,
+            gas_used: header.gas_used,
+        })
+    }
+
+    /// `highest_committed_block_header_v2` sends requests to get the header of the highest
+    /// committed block using V2 RPC.
+    #[cfg(feature = "v2")]
+    async fn highest_committed_block_header_v2(&self) -> Result<BlockHeaderV2, HttpErrorResponse> {
+        let block_hash = self
+            .highest_committed_block()
+            .await?
+            .block_hash
+            .ok_or_else(|| String::from("Highest committed block is not found."))?;
+
+        self.block_header_v2(&BlockHeaderRequest { block_hash })
+            .await?
+            .block_header
+            .ok_or_else(|| String::from("Block header is not found."))
+    }
+
+    /// `block_transactions_v2` sends a request to get the transactions in a block starting
+    /// from specified block hash using V2 RPC.
+    #[cfg(feature = "v2")]
+    pub async fn block_transactions_v2(
+        &self,
+        request: &BlockRequest,
+    ) -> Result<Vec<TransactionV1OrV2>, HttpErrorResponse> {
+        self.block_v2(request)
+            .await?
+            .block
+            .map(|block| block.transactions)
+            .ok_or_else(|| String::from("Block is not found."))
+    }
+
+    /// `account_transactions_in_block_v2` sends a request to get the transactions in a block
+    /// which involve an address using V2 RPC. A transaction matches if the address is its
+    /// signer, the recipient of one of its Transfer commands, or the target of one of its Call
+    /// commands, as defined by [involves_address](crate::utils::involves_address). Accounts
+    /// changed only by calls made internally by contracts are not matched.
+    /// # Arguments
+    /// * `block_hash` - hash of the block
+    /// * `address` - address to be matched
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn account_transactions_in_block_v2(
+        &self,
+        block_hash: CryptoHash,
+        address: PublicAddress,
+    ) -> Result<Vec<TransactionV1OrV2>, HttpErrorResponse> {
+        Ok(self
+            .block_transactions_v2(&BlockRequest { block_hash })
+            .await?
+            .into_iter()
+            .filter(|transaction| utils::involves_address(transaction, &address))
+            .collect())
+    }
+
+    /// `transactions_by_address_v2` scans a range of blocks using V2 RPC for transactions
+    /// which involve an address, as defined by [involves_address](crate::utils::involves_address).
+    /// The RPC has no address-indexed transaction query, so every block in the range is
+    /// fetched, one after another, until `limit` matching transactions are found. The scan also
+    /// stops at the first height without a committed block.
+    /// # Arguments
+    /// * `address` - address to be matched
+    /// * `block_heights` - range of block heights to be scanned
+    /// * `offset` - number of matching transactions in the first block of the range to be skipped
+    /// * `limit` - maximum number of matching transactions to be returned
+    ///
+    /// # Return
+    /// Page of matching transactions. The next page is fetched with
+    /// `next_height..block_heights.end` and `next_offset`.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn transactions_by_address_v2(
+        &self,
+        address: PublicAddress,
+        block_heights: Range<u64>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<TransactionsPage, HttpErrorResponse> {
+        let end = block_heights.end;
+        let mut page = TransactionsPage {
+            items: Vec::new(),
+            next_height: block_heights.start,
+            next_offset: offset,
+            has_more: !block_heights.is_empty(),
+        };
+
+        for block_height in block_heights {
+            if page.items.len() >= limit {
+                return Ok(page);
+            }
+
+            let block_hash = match self
+                .block_hash_by_height(&BlockHashByHeightRequest { block_height })
+                .await?
+                .block_hash
+            {
+                Some(block_hash) => block_hash,
+                None => {
+                    page.has_more = false;
+                    return Ok(page);
+                }
+            };
+
+            let matches = self
+                .block_transactions_v2(&BlockRequest { block_hash })
+                .await?
+                .into_iter()
+                .filter(|transaction| utils::involves_address(transaction, &address));
+            for (index, transaction) in matches.enumerate().skip(page.next_offset) {
+                if page.items.len() >= limit {
+                    page.next_offset = index;
+                    return Ok(page);
+                }
+                page.items.push(transaction);
+            }
+
+            page.next_height = block_height + 1;
+            page.next_offset = 0;
+            page.has_more = page.next_height < end;
+        }
+
+        Ok(page)
+    }
+
+    /// `await_confirmations_v2` waits until a transaction is included in a block and buried
+    /// under `confirmations` subsequent blocks, polling the network provider every
+    /// `poll_interval`. Polling and the timeout are driven by the tokio 1 timer.
+    /// # Arguments
+    /// * `transaction_hash` - hash of the transaction
+    /// * `confirmations` - number of blocks committed after the block of the transaction
+    /// * `timeout` - maximum duration of waiting
+    /// * `poll_interval` - delay between two polls
+    ///
+    /// # Return
+    /// Receipt of the transaction, or an error if `timeout` elapses first.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn await_confirmations_v2(
+        &self,
+        transaction_hash: CryptoHash,
+        confirmations: u64,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> Result<ReceiptResponseV2, HttpErrorResponse> {
+        let wait = async {
+            let block_height = loop {
+                let position = self
+                    .transaction_position(&TransactionPositionRequest { transaction_hash })
+                    .await?;
+                if let Some(block_hash) = position.block_hash {
+                    if let Some(block_height) = self.block_height_of(block_hash).await? {
+                        break block_height;
+                    }
+                }
+                runtime::sleep(poll_interval).await;
+            };
+
+            loop {
+                let highest_block_hash = self.highest_committed_block().await?.block_hash;
+                if let Some(block_hash) = highest_block_hash {
+                    if let Some(highest_height) = self.block_height_of(block_hash).await? {
+                        if highest_height >= block_height.saturating_add(confirmations) {
+                            break;
+                        }
+                    }
+                }
+                runtime::sleep(poll_interval).await;
+            }
+
+            self.receipt_v2(&ReceiptRequest { transaction_hash }).await
+        };
+
+        runtime::timeout(timeout, wait)
+            .await
+            .map_err(|_| String::from("Timed out waiting for confirmations."))?
+    }
+
+    /// `next_block_v2` sends requests to get the full block data of the block after a specified
+    /// block using V2 RPC.
+    /// # Arguments
+    /// * `block_hash` - hash of the current block
+    ///
+    /// # Return
+    /// None if the current block is the highest committed block. Error message if the current
+    /// block is not found.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn next_block_v2(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Result<Option<BlockResponseV2>, HttpErrorResponse> {
+        let block_height = self.existing_block_height(block_hash).await?;
+        self.block_at_height_v2(block_height + 1).await
+    }
+
+    /// `prev_block_v2` sends requests to get the full block data of the block before a
+    /// specified block using V2 RPC.
+    /// # Arguments
+    /// * `block_hash` - hash of the current block
+    ///
+    /// # Return
+    /// None if the current block is the genesis block. Error message if the current block is
+    /// not found.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn prev_block_v2(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Result<Option<BlockResponseV2>, HttpErrorResponse> {
+        match self.existing_block_height(block_hash).await?.checked_sub(1) {
+            Some(block_height) => self.block_at_height_v2(block_height).await,
+            None => Ok(None),
+        }
+    }
+
+    /// `existing_block_height` sends a request to get the height of a block which must exist.
+    #[cfg(feature = "v2")]
+    async fn existing_block_height(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Result<u64, HttpErrorResponse> {
+        self.block_height_of(block_hash)
+            .await?
+            .ok_or_else(|| String::from("Block is not found."))
+    }
+
+    /// `block_at_height_v2` sends requests to get the full block data at a height using V2 RPC,
+    /// if a block is committed at the height.
+    #[cfg(feature = "v2")]
+    async fn block_at_height_v2(
+        &self,
+        block_height: u64,
+    ) -> Result<Option<BlockResponseV2>, HttpErrorResponse> {
+        match self
+            .block_hash_by_height(&BlockHashByHeightRequest { block_height })
+            .await?
+            .block_hash
+        {
+            Some(block_hash) => Ok(Some(self.block_v2(&BlockRequest { block_hash }).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// `block_height_of` sends a request to get the height of a block, if the block exists.
+    #[cfg(feature = "v2")]
+    async fn block_height_of(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Result<Option<u64>, HttpErrorResponse> {
+        Ok(self
+            .block_height_by_hash(&BlockHeightByHashRequest { block_hash })
+            .await?
+            .block_height)
+    }
+
+    /// `account_balance_v2` sends a request to get the balance of an account using V2 RPC.
+    /// # Arguments
+    /// * `address` - address of the account
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn account_balance_v2(
+        &self,
+        address: PublicAddress,
+    ) -> Result<u64, HttpErrorResponse> {
+        match self.account_v2(address, false).await? {
+            Account::WithContract(account) => Ok(account.balance),
+            Account::WithoutContract(account) => Ok(account.balance),
+        }
+    }
+
+    /// `account_nonce_v2` sends a request to get the nonce of an account using V2 RPC.
+    /// # Arguments
+    /// * `address` - address of the account
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn account_nonce_v2(&self, address: PublicAddress) -> Result<u64, HttpErrorResponse> {
+        match self.account_v2(address, false).await? {
+            Account::WithContract(account) => Ok(account.nonce),
+            Account::WithoutContract(account) => Ok(account.nonce),
+        }
+    }
+
+    /// `contract_code_v2` sends a request to get the contract code (WASM bytecode) deployed
+    /// at an address using V2 RPC.
+    /// # Arguments
+    /// * `address` - address of the contract
+    ///
+    /// # Return
+    /// Error message if the account is not a contract.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn contract_code_v2(
+        &self,
+        address: PublicAddress,
+    ) -> Result<Vec<u8>, HttpErrorResponse> {
+        match self.account_v2(address, true).await? {
+            Account::WithContract(account) => account
+                .contract
+                .ok_or_else(|| String::from("Account is not a contract.")),
+            Account::WithoutContract(_) => Err(String::from(
+                "Contract code is not included in the state response.",
+            )),
+        }
+    }
+
+    /// `contract_info_v2` sends a single request to get the balance, nonce, CBI version, storage
+    /// hash and code of a contract using V2 RPC.
+    /// # Arguments
+    /// * `address` - address of the contract
+    ///
+    /// # Return
+    /// Error message if the account is not a contract.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn contract_info_v2(
+        &self,
+        address: PublicAddress,
+    ) -> Result<ContractInfo, HttpErrorResponse> {
+        match self.account_v2(address, true).await? {
+            Account::WithContract(account) => Ok(ContractInfo {
+                balance: account.balance,
+                nonce: account.nonce,
+                cbi_version: account.cbi_version,
+                storage_hash: account.storage_hash,
+                contract: account
+                    .contract
+                    .ok_or_else(|| String::from("Account is not a contract."))?,
+            }),
+            Account::WithoutContract(_) => Err(String::from(
+                "Contract code is not included in the state response.",
+            )),
+        }
+    }
+
+    /// `is_contract_v2` sends a request to check whether contract code is deployed at an
+    /// address using V2 RPC.
+    /// # Arguments
+    /// * `address` - address of the account
+    ///
+    /// # Return
+    /// false for an account without contract code. Errors only on request or response failures.
+    ///
+    #[cfg(feature = "v2")]
+    pub async fn is_contract_v2(&self, address: PublicAddress) -> Result<bool, HttpErrorResponse> {
+        match self.account_v2(address, true).await? {
+            Account::WithContract(account) => Ok(account.contract.is_some()),
+            Account::WithoutContract(_) => Ok(false),
+        }
+    }
+
+    /// `account_v2` sends a request to get the account data of a single account using V2 RPC.
+    #[cfg(feature = "v2")]
+    async fn account_v2(
+        &self,
+        address: PublicAddress,
+        include_contract: bool,
+    ) -> Result<Account, HttpErrorResponse> {
+        let request = StateRequest {
+            accounts: HashSet::from([address]),
+            include_contract,
+            storage_keys: HashMap::new(),
+        };
+
+        self.state_v2(&request)
+            .await?
+            .accounts
+            .remove(&address)
+            .ok_or_else(|| String::from("Account is not found in the state response."))
+    }
+}
+
+#[cfg(all(test, feature = "v2"))]
+mod tests {
+    use super::*;
+
+    use pchain_types::blockchain::{ExitCodeV2, ReceiptV2, TransactionV2};
+
+    use crate::test_server::serve_once;
+
+    fn transaction_request() -> TransactionRequest {
+        TransactionRequest {
+            transaction_hash: [1u8; 32],
+            include_receipt: false,
+        }
+    }
+
+    fn receipt_request() -> ReceiptRequest {
+        ReceiptRequest {
+            transaction_hash: [1u8; 32],
+        }
+    }
+
+    #[tokio::test]
+    async fn transaction_v2_opt_returns_none_if_not_found() {
+        let response = TransactionResponseV2 {
+            transaction: None,
+            receipt: None,
+            block_hash: None,
+            position: None,
+        };
+        let client = Client::new(&serve_once(response.serialize()).await);
+
+        let result = client.transaction_v2_opt(&transaction_request()).await;
+        assert!(matches!(result, Ok(None)));
+    }
+
+    #[tokio::test]
+    async fn transaction_v2_opt_returns_some_if_found() {
+        let transaction = TransactionV2 {
+            signer: [2u8; 32],
+            nonce: 0,
+            commands: Vec::new(),
+            gas_limit: 0,
+            max_base_fee_per_gas: 0,
+            priority_fee_per_gas: 0,
+            signature: [0u8; 64],
+            hash: [1u8; 32],
+        };
+        let response = TransactionResponseV2 {
+            transaction: Some(TransactionV1OrV2::V2(transaction)),
+            receipt: None,
+            block_hash: Some([3u8; 32]),
+            position: Some(0),
+        };
+        let client = Client::new(&serve_once(response.serialize()).await);
+
+        let result = client.transaction_v2_opt(&transaction_request()).await;
+        assert!(matches!(result, Ok(Some(found)) if found.block_hash == Some([3u8; 32])));
+    }
+
+    #[tokio::test]
+    async fn receipt_v2_opt_returns_none_if_not_found() {
+        let response = ReceiptResponseV2 {
+            transaction_hash: [1u8; 32],
+            receipt: None,
+            block_hash: None,
+            position: None,
+        };
+        let client = Client::new(&serve_once(response.serialize()).await);
+
+        let result = client.receipt_v2_opt(&receipt_request()).await;
+        assert!(matches!(result, Ok(None)));
+    }
+
+    #[tokio::test]
+    async fn receipt_v2_opt_returns_some_if_found() {
+        let response = ReceiptResponseV2 {
+            transaction_hash: [1u8; 32],
+            receipt: Some(ReceiptV2 {
+                gas_used: 0,
+                exit_code: ExitCodeV2::Ok,
+                command_receipts: Vec::new(),
+            }),
+            block_hash: Some([3u8; 32]),
+            position: Some(0),
+        };
+        let client = Client::new(&serve_once(response.serialize()).await);
+
+        let result = client.receipt_v2_opt(&receipt_request()).await;
+        assert!(matches!(result, Ok(Some(found)) if found.receipt.is_some()));
+    }
+
+    #[tokio::test]
+    async fn transaction_v2_opt_returns_err_on_response_failure() {
+        let client = Client::new(&serve_once(b"not borsh".to_vec()).await);
+
+        let result = client.transaction_v2_opt(&transaction_request()).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn env_var_distinguishes_missing_from_set() {
+        let name = "PCHAIN_CLIENT_TEST_ENV_VAR";
+
+        std::env::remove_var(name);
+        assert_eq!(env_var(name), Ok(None));
+
+        std::env::set_var(name, "http://localhost");
+        assert_eq!(env_var(name), Ok(Some("http://localhost".to_string())));
+        std::env::remove_var(name);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn env_var_rejects_non_unicode_value() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let name = "PCHAIN_CLIENT_TEST_ENV_VAR_NOT_UNICODE";
+        std::env::set_var(name, OsStr::from_bytes(&[0x66, 0x80]));
+        assert!(env_var(name).unwrap_err().contains("is invalid"));
+        std::env::remove_var(name);
+    }
+
+    #[test]
+    fn from_env_rejects_missing_or_invalid_variables() {
+        // A single test sets these variables, so that tests running in parallel do not race.
+        std::env::remove_var(ENV_CONNECT_TIMEOUT_SECS);
+        std::env::remove_var(ENV_RPC_URL);
+        assert_eq!(
+            Client::from_env().err().unwrap(),
+            "Environment variable PCHAIN_RPC_URL is missing."
+        );
+
+        std::env::set_var(ENV_RPC_URL, "");
+        assert!(Client::from_env()
+            .err()
+            .unwrap()
+            .starts_with("Environment variable PCHAIN_RPC_URL is invalid: "));
+
+        std::env::set_var(ENV_RPC_URL, "not a url");
+        assert!(Client::from_env()
+            .err()
+            .unwrap()
+            .starts_with("Environment variable PCHAIN_RPC_URL is invalid: "));
+
+        std::env::set_var(ENV_RPC_URL, "http://localhost:8080");
+        std::env::set_var(ENV_CONNECT_TIMEOUT_SECS, "ten");
+        assert!(Client::from_env()
+            .err()
+            .unwrap()
+            .starts_with("Environment variable PCHAIN_CONNECT_TIMEOUT_SECS is invalid: "));
+
+        std::env::set_var(ENV_CONNECT_TIMEOUT_SECS, "10");
+        let client = Client::from_env().unwrap();
+        assert_eq!(client.get_provider(), "http://localhost:8080");
+
+        std::env::remove_var(ENV_CONNECT_TIMEOUT_SECS);
+        std::env::remove_var(ENV_RPC_URL);
+    }
 }
@@ -6,6 +6,53 @@
 //! Error handling for this library, including error printing. This transforms the HTTP error
 //! code (integer in data type string) received from standard code to human-readable string.
 
+/// Error message of requests whose provider host cannot be resolved.
+pub(crate) const DNS_FAILURE: &str =
+    "DNS resolution failed. Please check the host of the provider URL.";
+
+/// Error message of requests whose TLS handshake with the provider fails.
+pub(crate) const TLS_FAILURE: &str =
+    "TLS handshake failed. Please check the certificate of the provider.";
+
+/// Error message of requests whose connection is refused by the provider.
+pub(crate) const CONNECTION_REFUSED: &str =
+    "Connection refused. Please check if the provider is live.";
+
+/// Error message of requests whose connection to the provider fails for other reasons, e.g. a
+/// timeout or an unreachable network.
+pub(crate) const CONNECT_FAILURE: &str =
+    "Connection failed. Please check the network path to the provider.";
+
+/// Error message of requests which are redirected more times than allowed.
+pub(crate) const TOO_MANY_REDIRECTS: &str =
+    "Too many redirects. The provider redirected more times than allowed.";
+
+/// Error message of requests which fail fast because the provider was found down.
+pub(crate) const PROVIDER_UNREACHABLE: &str =
+    "Provider is unreachable according to the last health check.";
+
+/// Error message of requests which are redirected from HTTPS to HTTP.
+pub(crate) const INSECURE_REDIRECT: &str =
+    "Insecure redirect. The provider redirected an HTTPS request to HTTP.";
+
+/// Error message of requests which the provider answers with 503 Service Unavailable.
+pub(crate) const SERVICE_UNAVAILABLE: &str =
+    "Service Unavailable. The provider is temporarily unable to handle requests.";
+
+/// `service_unavailable` creates the error message of a 503 Service Unavailable response.
+/// # Arguments
+/// * `retry_after` - delay in seconds from the Retry-After header, if present.
+///
+/// # Return
+/// Error message in String.
+///
+pub(crate) fn service_unavailable(retry_after: Option<u64>) -> HttpErrorResponse {
+    match retry_after {
+        Some(seconds) => format!("{} Retry after {} seconds.", SERVICE_UNAVAILABLE, seconds),
+        None => SERVICE_UNAVAILABLE.to_string(),
+    }
+}
+
 /// `new` creates an ErrorResponse given an error code from RPC endpoint.
 /// # Arguments
 /// * `error_code` - error code received from RPC endpoint.
@@ -14,11 +61,27 @@
 /// Error message in String.
 ///
 pub(crate) fn new(error_code: String) -> HttpErrorResponse {
+    let is_classified = [
+        PROVIDER_UNREACHABLE,
+        SERVICE_UNAVAILABLE,
+        DNS_FAILURE,
+        TLS_FAILURE,
+        CONNECTION_REFUSED,
+        CONNECT_FAILURE,
+    ]
+    .iter()
+    .any(|message| error_code.starts_with(message));
+    if is_classified {
+        return error_code;
+    }
+
     match error_code.parse::<i16>() {
         Ok(err) => response(err as u16),
         Err(_) => {
-            if error_code.contains("Connection refused") {
-                "Connection refused. Please check if the provider is live.".to_string()
+            if error_code.contains(INSECURE_REDIRECT) {
+                INSECURE_REDIRECT.to_string()
+            } else if error_code.contains(TOO_MANY_REDIRECTS) {
+                TOO_MANY_REDIRECTS.to_string()
             } else {
                 format!("Unknown error occured. {}", error_code)
             }
@@ -26,6 +89,58 @@ pub(crate) fn new(error_code: String) -> HttpErrorResponse {
     }
 }
 
+/// `transport_error` describes an error from sending a request or receiving a response,
+/// including its whole source chain. Connect errors are classified as DNS, TLS, refused or other
+/// connect failures by inspecting the source chain only, since the message of the error itself
+/// contains the request URL.
+/// # Arguments
+/// * `error` - error returned by reqwest.
+///
+/// # Return
+/// Error message in String.
+///
+pub(crate) fn transport_error(error: &reqwest::Error) -> String {
+    let mut sources = Vec::new();
+    let mut source = std::error::Error::source(error);
+    while let Some(e) = source {
+        sources.push(e);
+        source = e.source();
+    }
+
+    let mut details = String::new();
+    for e in &sources {
+        let description = e.to_string();
+        if !details.contains(&description) {
+            details.push_str(": ");
+            details.push_str(&description);
+        }
+    }
+
+    if !error.is_connect() {
+        return format!("{}{}", error, details);
+    }
+
+    let lowercase = details.to_lowercase();
+    let is_refused = sources.iter().any(|e| {
+        e.downcast_ref::<std::io::Error>()
+            .is_some_and(|e| e.kind() == std::io::ErrorKind::ConnectionRefused)
+    });
+    let category = if lowercase.contains("dns error") {
+        DNS_FAILURE
+    } else if is_refused {
+        CONNECTION_REFUSED
+    } else if lowercase.contains("certificate")
+        || lowercase.contains("tls")
+        || lowercase.contains("ssl")
+        || lowercase.contains("handshake")
+    {
+        TLS_FAILURE
+    } else {
+        CONNECT_FAILURE
+    };
+    format!("{} Cause{}", category, details)
+}
+
 /// `response` is a helper which receives an error code and sends back a human-readable message
 /// to the user.
 /// # Arguments
@@ -41,6 +156,7 @@ fn response(error_code: u16) -> HttpErrorResponse {
         response_code::status400::INCOMPATIBLE_RESPONSE => String::from("Incompatible response of RPC version."),
         response_code::status500::VIEW_SERVICE_CHANNEL_ERROR => String::from("Internal Server Error. Server is busy and failed to handle new request."),
         response_code::status500::VIEW_SERVICE_REQUEST_TIMEOUT => String::from("Internal Server Error. Request Timeout."),
+        response_code::status::PAYLOAD_TOO_LARGE => String::from("Payload Too Large. The request exceeds the size limit of the provider."),
         _ => panic!("Irrecoverable Error. Unknown error code {} encountered. Please post an issue on ParallelChain Github Repository.", error_code),
     }
 }
@@ -56,6 +172,54 @@ pub(crate) mod response_code {
         pub(crate) const VIEW_SERVICE_CHANNEL_ERROR: u16 = 0x57D;
         pub(crate) const VIEW_SERVICE_REQUEST_TIMEOUT: u16 = 0x57E;
     }
+    /// HTTP status codes which are returned without a Fullnode error code in the body.
+    pub(crate) mod status {
+        pub(crate) const PAYLOAD_TOO_LARGE: u16 = 413;
+    }
 }
 
 pub type HttpErrorResponse = String;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_unavailable_with_retry_after() {
+        assert_eq!(
+            service_unavailable(Some(30)),
+            format!("{} Retry after 30 seconds.", SERVICE_UNAVAILABLE)
+        );
+    }
+
+    #[test]
+    fn service_unavailable_without_retry_after() {
+        assert_eq!(service_unavailable(None), SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn new_keeps_classified_transport_errors() {
+        for category in [
+            DNS_FAILURE,
+            TLS_FAILURE,
+            CONNECTION_REFUSED,
+            CONNECT_FAILURE,
+        ] {
+            let message = format!("{} Cause: detail", category);
+            assert_eq!(new(message.clone()), message);
+        }
+    }
+
+    #[tokio::test]
+    async fn transport_error_ignores_tls_words_in_url() {
+        // Bind and drop a listener to get a local port which refuses connections.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let error = reqwest::get(format!("http://{}/ssl-node/tls/certificate", addr))
+            .await
+            .unwrap_err();
+        assert!(transport_error(&error).starts_with(CONNECTION_REFUSED));
+    }
+}
@@ -29,7 +29,44 @@
 mod client;
 pub use client::Client;
 
+#[cfg(feature = "v2")]
+mod batch;
+#[cfg(feature = "v2")]
+pub use batch::{Batch, BatchResults};
+
+mod types;
+pub use types::{ChainInfo, ContractInfo, GasParams, PreparedRequest, TransactionsPage};
+
+pub mod middleware;
+
 mod networking;
-pub use networking::NetworkProvider;
+pub use networking::{Encoding, NetworkProvider, RawResponse};
 
 mod error;
+
+#[cfg_attr(not(feature = "v2"), allow(dead_code))]
+mod runtime;
+
+#[cfg(feature = "pretty")]
+mod pretty;
+#[cfg(feature = "pretty")]
+pub use pretty::Pretty;
+
+#[cfg(feature = "v2")]
+mod watcher;
+#[cfg(feature = "v2")]
+pub use watcher::{ReceiptHandle, ReceiptWatcher};
+
+mod oneshot;
+#[cfg(feature = "blocking")]
+pub use oneshot::blocking;
+pub use oneshot::get_highest_block;
+#[cfg(feature = "v2")]
+pub use oneshot::submit_transaction;
+
+pub mod paging;
+
+pub mod utils;
+
+#[cfg(test)]
+mod test_server;
@@ -0,0 +1,43 @@
+/*
+    Copyright © 2023, ParallelChain Lab
+    Licensed under the Apache License, Version 2.0: http://www.apache.org/licenses/LICENSE-2.0
+*/
+
+//! Hooks for modifying outgoing HTTP requests, e.g. to add authentication headers or tracing
+//! context. Middlewares are added to the Client by
+//! [add_middleware](crate::Client::add_middleware), and applied to every request in the order
+//! in which they were added, after the headers set by the Client itself. A later middleware can
+//! therefore overwrite headers set by an earlier one or by the Client.
+
+use reqwest::{header::HeaderMap, RequestBuilder};
+
+/// A trait used for modifying outgoing HTTP requests.
+pub trait RequestMiddleware: Send + Sync {
+    /// modifies an outgoing request before it is sent.
+    ///
+    /// # Arguments
+    /// * `request` - request to be sent
+    ///
+    /// # Return
+    /// the modified request.
+    fn handle(&self, request: RequestBuilder) -> RequestBuilder;
+}
+
+/// [StaticHeaders] is a [RequestMiddleware] which adds a fixed set of headers to every request.
+pub struct StaticHeaders {
+    /// `headers` denotes the headers added to every request.
+    headers: HeaderMap,
+}
+
+impl StaticHeaders {
+    /// `new` creates a middleware which adds `headers` to every request.
+    pub fn new(headers: HeaderMap) -> Self {
+        Self { headers }
+    }
+}
+
+impl RequestMiddleware for StaticHeaders {
+    fn handle(&self, request: RequestBuilder) -> RequestBuilder {
+        request.headers(self.headers.clone())
+    }
+}
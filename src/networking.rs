@@ -6,11 +6,78 @@
 //! HTTP networking with specific fullnode.
 
 use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use std::{
+    convert::TryFrom,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{error, middleware::RequestMiddleware, utils::ToBase64UrlDisplay};
+
+/// Default timeout for establishing a connection to the provider.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default maximum number of redirects followed for a request.
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+/// Maximum number of bytes of an error response body to be retained.
+const MAX_RETAINED_ERROR_BODY_LEN: usize = 64 * 1024;
+
+/// Maximum number of bytes preallocated for a response body according to its Content-Length.
+/// Larger bodies are still read in full, growing the buffer as chunks arrive.
+const MAX_PREALLOCATED_BODY_LEN: usize = 16 * 1024 * 1024;
+
+/// [Encoding] denotes the wire format of request and response bodies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// Borsh, as specified in pchain_types. Supported by every endpoint.
+    #[default]
+    Borsh,
+    /// JSON. Reserved for endpoints which may offer a JSON variant in the future.
+    Json,
+}
+
+impl Encoding {
+    /// `mime_type` returns the value of the `Accept` header for this encoding.
+    pub(crate) fn mime_type(&self) -> &'static str {
+        match self {
+            Encoding::Borsh => "application/octet-stream",
+            Encoding::Json => "application/json",
+        }
+    }
+}
 
 /// [Networking] houses all HTTP methods used by the Client to serve responses to the user.
 pub(crate) struct Networking {
     /// `provider` denotes the current ParallelChain Fullnode Provider URL.
     provider: String,
+    /// `base_path` denotes the normalized path prefix of the RPC endpoints, without leading or
+    /// trailing slashes. Empty if the endpoints are served at the root of the provider.
+    base_path: String,
+    /// `settings` denotes the settings which the inner reqwest::Client is built with.
+    settings: HttpSettings,
+    /// `encoding` denotes the wire format accepted from the provider.
+    encoding: Encoding,
+    /// `retain_error_body` denotes whether the body of the last error response is retained.
+    retain_error_body: bool,
+    /// `last_error_body` denotes the retained body of the last error response.
+    last_error_body: Mutex<Option<bytes::Bytes>>,
+    /// `last_retry_after` denotes the Retry-After delay of the last response, if it was a 503
+    /// Service Unavailable response with a delay in seconds.
+    last_retry_after: Mutex<Option<Duration>>,
+    /// `in_flight` limits the number of concurrent requests, if set.
+    in_flight: Option<Arc<Semaphore>>,
+    /// `fail_fast` denotes whether requests fail immediately while the provider is down.
+    fail_fast: bool,
+    /// `provider_up` denotes the result of the last health check of the current provider.
+    provider_up: AtomicBool,
+    /// `middlewares` denotes the middlewares applied to every request.
+    middlewares: Vec<Box<dyn RequestMiddleware>>,
     /// `client` denotes an instance of reqwest::Client for serving HTTP requests.
     pub client: reqwest::Client,
 }
@@ -26,19 +93,108 @@ impl Networking {
     pub fn new(provider: String) -> Self {
         Networking {
             provider,
-            client: reqwest::Client::builder()
-                .connect_timeout(std::time::Duration::from_secs(10))
-                .build()
-                .expect("TLS backend cannot be initialized"),
+            base_path: String::new(),
+            encoding: Encoding::default(),
+            retain_error_body: false,
+            last_error_body: Mutex::new(None),
+            last_retry_after: Mutex::new(None),
+            settings: HttpSettings::default(),
+            in_flight: None,
+            fail_fast: false,
+            provider_up: AtomicBool::new(true),
+            middlewares: Vec::new(),
+            client: build_client(&HttpSettings::default()),
         }
     }
 
+    /// `set_connect_timeout` sets the timeout for establishing a connection to the provider.
+    /// The inner reqwest::Client is rebuilt, so its connection pool is reset.
+    /// # Arguments
+    /// * `timeout` - connect timeout
+    ///
+    pub fn set_connect_timeout(&mut self, timeout: Duration) {
+        self.settings.connect_timeout = timeout;
+        self.client = build_client(&self.settings);
+    }
+
+    /// `set_danger_accept_invalid_certs` sets whether invalid TLS certificates of the provider,
+    /// e.g. self-signed ones, are accepted. The inner reqwest::Client is rebuilt, so its
+    /// connection pool is reset. The flag is passed to reqwest as is. Unit tests only cover
+    /// that it reaches the settings: testing a handshake with a self-signed server would need a
+    /// TLS server and certificate generation as dev-dependencies.
+    /// # Arguments
+    /// * `accept_invalid_certs` - true to accept invalid certificates
+    ///
+    pub fn set_danger_accept_invalid_certs(&mut self, accept_invalid_certs: bool) {
+        if accept_invalid_certs {
+            log::warn!(
+                "TLS certificate verification is disabled. This must not be used in production."
+            );
+        }
+        self.settings.accept_invalid_certs = accept_invalid_certs;
+        self.client = build_client(&self.settings);
+    }
+
+    /// `set_pool_max_idle_per_host` sets the maximum number of idle connections kept per host.
+    /// The inner reqwest::Client is rebuilt, so its connection pool is reset.
+    /// # Arguments
+    /// * `max` - maximum number of idle connections per host
+    ///
+    pub fn set_pool_max_idle_per_host(&mut self, max: usize) {
+        self.settings.pool_max_idle_per_host = max;
+        self.client = build_client(&self.settings);
+    }
+
+    /// `set_max_redirects` sets the maximum number of redirects followed for a request. The
+    /// inner reqwest::Client is rebuilt, so its connection pool is reset.
+    /// # Arguments
+    /// * `max` - maximum number of redirects
+    ///
+    pub fn set_max_redirects(&mut self, max: usize) {
+        self.settings.max_redirects = max;
+        self.client = build_client(&self.settings);
+    }
+
+    /// `set_encoding` sets the wire format accepted from the provider.
+    /// # Arguments
+    /// * `encoding` - wire format of request and response bodies
+    ///
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+    }
+
+    /// `get_encoding` gets the wire format accepted from the provider.
+    ///
+    pub fn get_encoding(&self) -> Encoding {
+        self.encoding
+    }
+
     /// `set_provider` sets a new network provider for Client.
     /// # Arguments
     /// * `url` - ParallelChain RPC base network URL
     ///
     pub fn set_provider(&mut self, url: &str) {
         self.provider = url.to_string();
+        self.provider_up.store(true, Ordering::Relaxed);
+    }
+
+    /// `set_base_path` validates and sets the path prefix of the RPC endpoints, e.g.
+    /// `pchain/mainnet`. Leading and trailing slashes are ignored.
+    /// # Arguments
+    /// * `base_path` - path prefix of the RPC endpoints
+    ///
+    pub fn set_base_path(&mut self, base_path: &str) -> Result<(), String> {
+        self.base_path = normalize_base_path(base_path)?;
+        Ok(())
+    }
+
+    /// `endpoint_url` composes the URL of an endpoint from a provider and the base path.
+    /// # Arguments
+    /// * `provider` - ParallelChain RPC base network URL
+    /// * `endpoint_path` - path of the endpoint, e.g. `state/v2`
+    ///
+    fn endpoint_url(&self, provider: &str, endpoint_path: &str) -> String {
+        build_url(provider, &self.base_path, endpoint_path)
     }
 
     /// `get_provider` get the current network provider base url.
@@ -51,46 +207,146 @@ impl Networking {
     /// provider is up.
     ///
     pub async fn is_provider_up(&self) -> bool {
-        let response = self.get_response("").await;
+        let response = self.get_response_unchecked("").await;
 
-        response.is_ok()
+        let is_up = response.is_ok();
+        self.provider_up.store(is_up, Ordering::Relaxed);
+        is_up
     }
 
-    /// `post_request` sends a POST request to the network provider for Client.
+    /// `ping` sends the health check GET request to a network provider and measures its round
+    /// trip, from sending the request until the whole response body is received. Waiting for a
+    /// free in-flight slot is excluded. DNS resolution and TLS setup are included unless a
+    /// pooled connection to the provider is reused, so the first ping to a provider is slower.
+    /// # Arguments
+    /// * `provider` - ParallelChain RPC base network URL
+    ///
+    pub async fn ping(&self, provider: &str) -> Result<Duration, String> {
+        let url = self.endpoint_url(provider, "");
+        let _permit = self.acquire_in_flight().await;
+
+        let start = Instant::now();
+        let response = self
+            .get_request(&url)
+            .await
+            .map_err(|e| error::transport_error(&e))?;
+        self.read_response(response).await?;
+        Ok(start.elapsed())
+    }
+
+    /// `set_fail_fast` sets whether requests to the current provider fail immediately while the
+    /// last health check by [is_provider_up](Networking::is_provider_up) found it down.
+    /// # Arguments
+    /// * `fail_fast` - true to fail fast
+    ///
+    pub fn set_fail_fast(&mut self, fail_fast: bool) {
+        self.fail_fast = fail_fast;
+    }
+
+    /// `check_fail_fast` returns an error if fail fast is enabled and the current provider was
+    /// found down by the last health check.
+    fn check_fail_fast(&self) -> Result<(), String> {
+        if self.fail_fast && !self.provider_up.load(Ordering::Relaxed) {
+            Err(error::PROVIDER_UNREACHABLE.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// `post_request` sends a POST request to the network provider for Client. Headers of this
+    /// request are applied after the middlewares, so they take precedence.
     /// # Arguments
     /// * `request_url` - The request URL
     /// * `body` - serialized pchain_types::SignedTx
+    /// * `headers` - headers of this request only
     ///
     pub async fn post_request(
         &self,
         request_url: &str,
-        body: Vec<u8>,
+        body: bytes::Bytes,
+        headers: HeaderMap,
     ) -> Result<reqwest::Response, reqwest::Error> {
-        self.client.post(request_url).body(body).send().await
+        let request = self
+            .client
+            .post(request_url)
+            .header(reqwest::header::ACCEPT, self.encoding.mime_type())
+            .body(body);
+
+        self.apply_middlewares(request)
+            .headers(headers)
+            .send()
+            .await
     }
 
-    /// `post_response` is a helper to return server-side responses from HTTP `POST methods`
-    /// defined in this namespace.
+    /// `post_response_with_headers` is a helper to return server-side responses from HTTP
+    /// `POST methods` defined in this namespace, with extra headers for this request only.
     /// # Arguments
     /// * `request_url` - The request URL
-    /// * `data` - Vector of bytes serialized from generic types
+    /// * `data` - bytes serialized from generic types
+    /// * `headers` - headers of this request only
     ///
-    pub async fn post_response(
+    pub async fn post_response_with_headers(
         &self,
         request_url: &str,
-        data: Vec<u8>,
+        data: bytes::Bytes,
+        headers: HeaderMap,
     ) -> Result<bytes::Bytes, String> {
-        let url = format!("{}/{}", &self.provider, request_url);
+        self.check_fail_fast()?;
+        self.post_response_to(&self.provider, request_url, data, headers)
+            .await
+    }
+
+    /// `post_response_to` is a helper to return server-side responses from HTTP `POST methods`
+    /// sent to a provider other than the current network provider.
+    /// # Arguments
+    /// * `provider` - ParallelChain RPC base network URL
+    /// * `request_url` - The request URL
+    /// * `data` - bytes serialized from generic types
+    /// * `headers` - headers of this request only
+    ///
+    pub async fn post_response_to(
+        &self,
+        provider: &str,
+        request_url: &str,
+        data: bytes::Bytes,
+        headers: HeaderMap,
+    ) -> Result<bytes::Bytes, String> {
+        let url = self.endpoint_url(provider, request_url);
+        let _permit = self.acquire_in_flight().await;
 
         let response = self
-            .post_request(&url, data)
+            .post_request(&url, data, headers)
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| error::transport_error(&e))?;
 
-        match response.status() {
-            reqwest::StatusCode::OK => Ok(response.bytes().await.map_err(|e| e.to_string())?),
-            _ => Err(response.text().await.map_err(|e| e.to_string())?),
-        }
+        self.read_response(response).await
+    }
+
+    /// `post_raw_response` sends a POST request to the current network provider and returns the
+    /// response without checking its status or reading its body. The in-flight slot is held by
+    /// the returned [RawResponse] until it is dropped.
+    /// # Arguments
+    /// * `request_url` - The request URL
+    /// * `data` - bytes serialized from generic types
+    ///
+    pub async fn post_raw_response(
+        &self,
+        request_url: &str,
+        data: bytes::Bytes,
+    ) -> Result<RawResponse, String> {
+        self.check_fail_fast()?;
+        let url = self.endpoint_url(&self.provider, request_url);
+        let permit = self.acquire_in_flight().await;
+
+        let response = self
+            .post_request(&url, data, HeaderMap::new())
+            .await
+            .map_err(|e| error::transport_error(&e))?;
+
+        Ok(RawResponse {
+            response,
+            _permit: permit,
+        })
     }
 
     /// `get_request` sends a GET request to the network provider.
@@ -101,7 +357,28 @@ impl Networking {
         &self,
         request_url: &str,
     ) -> Result<reqwest::Response, reqwest::Error> {
-        self.client.get(request_url).send().await
+        let request = self
+            .client
+            .get(request_url)
+            .header(reqwest::header::ACCEPT, self.encoding.mime_type());
+
+        self.apply_middlewares(request).send().await
+    }
+
+    /// `add_middleware` adds a middleware which is applied to every subsequent request, after
+    /// the middlewares added before it.
+    /// # Arguments
+    /// * `middleware` - middleware to be added
+    ///
+    pub fn add_middleware(&mut self, middleware: Box<dyn RequestMiddleware>) {
+        self.middlewares.push(middleware);
+    }
+
+    /// `apply_middlewares` applies the middlewares to a request in the order they were added.
+    fn apply_middlewares(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        self.middlewares
+            .iter()
+            .fold(request, |request, middleware| middleware.handle(request))
     }
 
     /// `get_response` is a helper to return server-side responses from HTTP `GET methods`
@@ -110,14 +387,319 @@ impl Networking {
     /// * `request_url` - The request URL
     ///
     pub async fn get_response(&self, request_url: &str) -> Result<bytes::Bytes, String> {
-        let url = format!("{}/{}", &self.provider, request_url);
-        let response = self.get_request(&url).await.map_err(|e| e.to_string())?;
+        self.check_fail_fast()?;
+        self.get_response_unchecked(request_url).await
+    }
+
+    /// `get_response_unchecked` is [get_response](Networking::get_response) without the fail
+    /// fast check, used by health checks.
+    /// # Arguments
+    /// * `request_url` - The request URL
+    ///
+    async fn get_response_unchecked(&self, request_url: &str) -> Result<bytes::Bytes, String> {
+        let url = self.endpoint_url(&self.provider, request_url);
+        let _permit = self.acquire_in_flight().await;
+        let response = self
+            .get_request(&url)
+            .await
+            .map_err(|e| error::transport_error(&e))?;
+
+        self.read_response(response).await
+    }
+
+    /// `set_max_in_flight_requests` sets the maximum number of concurrent requests sent by
+    /// [post_response_with_headers](Networking::post_response_with_headers),
+    /// [post_raw_response](Networking::post_raw_response) and
+    /// [get_response](Networking::get_response).
+    /// `None` removes the limit.
+    /// # Arguments
+    /// * `max` - maximum number of concurrent requests, which must be greater than zero
+    ///
+    pub fn set_max_in_flight_requests(&mut self, max: Option<usize>) -> Result<(), String> {
+        if max == Some(0) {
+            return Err("Maximum number of in-flight requests must be greater than zero.".into());
+        }
+        self.in_flight = max.map(|max| Arc::new(Semaphore::new(max)));
+        Ok(())
+    }
+
+    /// `acquire_in_flight` waits for a free slot if the number of concurrent requests is
+    /// limited. The slot is released when the returned permit is dropped.
+    async fn acquire_in_flight(&self) -> Option<OwnedSemaphorePermit> {
+        match &self.in_flight {
+            Some(semaphore) => Some(
+                Arc::clone(semaphore)
+                    .acquire_owned()
+                    .await
+                    .expect("In-flight semaphore is never closed"),
+            ),
+            None => None,
+        }
+    }
+
+    /// `read_response` returns the body of a successful response, or the body of an error
+    /// response as the error message. Statuses which carry no Fullnode error code in the body
+    /// are returned as their status code instead. The error body is retained if enabled.
+    /// # Arguments
+    /// * `response` - response received from the network provider
+    ///
+    async fn read_response(&self, response: reqwest::Response) -> Result<bytes::Bytes, String> {
+        let status = response.status();
+        let retry_after = retry_after(&response);
+        let body = read_body(response)
+            .await
+            .map_err(|e| error::transport_error(&e))?;
+
+        *self.last_retry_after.lock().unwrap() = match status {
+            reqwest::StatusCode::SERVICE_UNAVAILABLE => retry_after.map(Duration::from_secs),
+            _ => None,
+        };
+
+        if status == reqwest::StatusCode::OK {
+            self.set_last_error_body(None);
+            return Ok(body);
+        }
 
-        match response.status() {
-            reqwest::StatusCode::OK => Ok(response.bytes().await.map_err(|e| e.to_string())?),
-            _ => Err(response.text().await.map_err(|e| e.to_string())?),
+        self.set_last_error_body(Some(&body));
+        match status {
+            reqwest::StatusCode::PAYLOAD_TOO_LARGE => {
+                Err(error::response_code::status::PAYLOAD_TOO_LARGE.to_string())
+            }
+            reqwest::StatusCode::SERVICE_UNAVAILABLE => {
+                Err(error::service_unavailable(retry_after))
+            }
+            _ => Err(error_body_message(&body)),
         }
     }
+
+    /// `set_retain_error_body` sets whether the body of the last error response is retained.
+    /// Disabling it also clears the retained body.
+    /// # Arguments
+    /// * `retain` - true to retain the body of the last error response
+    ///
+    pub fn set_retain_error_body(&mut self, retain: bool) {
+        self.retain_error_body = retain;
+        if !retain {
+            *self.last_error_body.lock().unwrap() = None;
+        }
+    }
+
+    /// `last_error_body` gets the retained body of the last error response.
+    ///
+    pub fn last_error_body(&self) -> Option<bytes::Bytes> {
+        self.last_error_body.lock().unwrap().clone()
+    }
+
+    /// `last_retry_after` gets the Retry-After delay of the last response, if it was a 503
+    /// Service Unavailable response with a delay in seconds.
+    ///
+    pub fn last_retry_after(&self) -> Option<Duration> {
+        *self.last_retry_after.lock().unwrap()
+    }
+
+    /// `set_last_error_body` retains at most [MAX_RETAINED_ERROR_BODY_LEN] bytes of an error
+    /// body, or clears the retained body if `body` is None.
+    fn set_last_error_body(&self, body: Option<&bytes::Bytes>) {
+        if !self.retain_error_body {
+            return;
+        }
+        *self.last_error_body.lock().unwrap() =
+            body.map(|body| body.slice(..body.len().min(MAX_RETAINED_ERROR_BODY_LEN)));
+    }
+}
+
+/// [RawResponse] is a response returned without checking its status or reading its body. If the
+/// number of in-flight requests is limited, it holds its slot until it is dropped, so the body
+/// is read within the limit.
+#[derive(Debug)]
+pub struct RawResponse {
+    /// `response` denotes the response received from the network provider.
+    response: reqwest::Response,
+    /// `_permit` denotes the in-flight slot held by this response, if the number of in-flight
+    /// requests is limited.
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl RawResponse {
+    /// `status` returns the status code of the response.
+    pub fn status(&self) -> reqwest::StatusCode {
+        self.response.status()
+    }
+
+    /// `headers` returns the headers of the response.
+    pub fn headers(&self) -> &HeaderMap {
+        self.response.headers()
+    }
+
+    /// `chunk` reads the next chunk of the response body. Returns `None` once the body is
+    /// exhausted.
+    pub async fn chunk(&mut self) -> Result<Option<bytes::Bytes>, String> {
+        self.response
+            .chunk()
+            .await
+            .map_err(|e| error::transport_error(&e))
+    }
+
+    /// `bytes` reads the full response body. The in-flight slot is released afterwards.
+    pub async fn bytes(self) -> Result<bytes::Bytes, String> {
+        read_body(self.response)
+            .await
+            .map_err(|e| error::transport_error(&e))
+    }
+}
+
+/// `build_url` joins a provider URL, a base path and an endpoint path with exactly one slash
+/// between each non-empty part, regardless of trailing slashes on the provider or base path and
+/// leading slashes on the base path or endpoint path.
+/// # Arguments
+/// * `provider` - ParallelChain RPC base network URL, e.g. `https://gateway/`
+/// * `base_path` - path prefix of the RPC endpoints, e.g. `pchain/mainnet`, or empty
+/// * `path` - path of the endpoint, e.g. `/state/v2`
+///
+pub(crate) fn build_url(provider: &str, base_path: &str, path: &str) -> String {
+    let mut url = provider.trim_end_matches('/').to_string();
+    let base_path = base_path.trim_matches('/');
+    if !base_path.is_empty() {
+        url.push('/');
+        url.push_str(base_path);
+    }
+    url.push('/');
+    url.push_str(path.trim_start_matches('/'));
+    url
+}
+
+/// `normalize_base_path` strips leading and trailing slashes from a base path, and rejects base
+/// paths which cannot be a plain URL path prefix.
+/// # Arguments
+/// * `base_path` - path prefix of the RPC endpoints
+///
+fn normalize_base_path(base_path: &str) -> Result<String, String> {
+    let normalized = base_path.trim_matches('/');
+    let is_valid = !normalized
+        .chars()
+        .any(|c| c == '?' || c == '#' || c.is_whitespace())
+        && normalized
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+
+    if normalized.is_empty() || is_valid {
+        Ok(normalized.to_string())
+    } else {
+        Err(format!("Invalid base path: {}", base_path))
+    }
+}
+
+/// [HttpSettings] houses the settings which the inner reqwest::Client is built with.
+#[derive(Clone, Debug)]
+struct HttpSettings {
+    /// `connect_timeout` denotes the timeout for establishing a connection to the provider.
+    connect_timeout: Duration,
+    /// `accept_invalid_certs` denotes whether invalid TLS certificates are accepted.
+    accept_invalid_certs: bool,
+    /// `pool_max_idle_per_host` denotes the maximum number of idle connections kept per host.
+    pool_max_idle_per_host: usize,
+    /// `max_redirects` denotes the maximum number of redirects followed for a request.
+    max_redirects: usize,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            accept_invalid_certs: false,
+            pool_max_idle_per_host: usize::MAX,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+        }
+    }
+}
+
+/// `redirect_policy` follows at most `max_redirects` redirects, and refuses redirects which
+/// downgrade the scheme from HTTPS to HTTP. Upgrades from HTTP to HTTPS are followed.
+fn redirect_policy(max_redirects: usize) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        match check_redirect(attempt.previous(), attempt.url(), max_redirects) {
+            Ok(()) => attempt.follow(),
+            Err(message) => attempt.error(message),
+        }
+    })
+}
+
+/// `check_redirect` decides whether a redirect is followed according to [redirect_policy].
+/// # Arguments
+/// * `previous` - URLs requested so far, starting with the original URL
+/// * `next` - URL which the last response redirects to
+/// * `max_redirects` - maximum number of redirects followed for a request
+///
+/// # Return
+/// Error message if the redirect is refused.
+///
+fn check_redirect(
+    previous: &[reqwest::Url],
+    next: &reqwest::Url,
+    max_redirects: usize,
+) -> Result<(), &'static str> {
+    let is_downgrade = previous
+        .last()
+        .is_some_and(|previous| previous.scheme() == "https")
+        && next.scheme() == "http";
+
+    if is_downgrade {
+        Err(error::INSECURE_REDIRECT)
+    } else if previous.len() > max_redirects {
+        Err(error::TOO_MANY_REDIRECTS)
+    } else {
+        Ok(())
+    }
+}
+
+/// `read_body` reads the body of a response into a buffer preallocated according to its
+/// Content-Length, capped at [MAX_PREALLOCATED_BODY_LEN].
+async fn read_body(mut response: reqwest::Response) -> Result<bytes::Bytes, reqwest::Error> {
+    let capacity = response
+        .content_length()
+        .and_then(|len| usize::try_from(len).ok())
+        .unwrap_or(0)
+        .min(MAX_PREALLOCATED_BODY_LEN);
+
+    let mut body = bytes::BytesMut::with_capacity(capacity);
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body.freeze())
+}
+
+/// `error_body_message` returns an error body as the error message. A body which is not valid
+/// UTF-8 is Base64URL encoded instead, so that its bytes are preserved.
+fn error_body_message(body: &[u8]) -> String {
+    match std::str::from_utf8(body) {
+        Ok(text) => text.to_string(),
+        Err(_) => format!("Binary error body (Base64URL): {}", body.to_base64url()),
+    }
+}
+
+/// `retry_after` reads the delay in seconds from the Retry-After header of a response. HTTP-date
+/// values are not supported and read as absent.
+fn retry_after(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// `build_client` creates an instance of reqwest::Client with the given settings. Panics if
+/// failed to initialized TLS backend connection.
+fn build_client(settings: &HttpSettings) -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(settings.connect_timeout)
+        .danger_accept_invalid_certs(settings.accept_invalid_certs)
+        .pool_max_idle_per_host(settings.pool_max_idle_per_host)
+        .redirect(redirect_policy(settings.max_redirects))
+        .build()
+        .expect("TLS backend cannot be initialized")
 }
 
 /// A trait used for Parallelchain RPC API provider setup.
@@ -138,3 +720,288 @@ pub trait NetworkProvider {
     /// true if server is up, otherwise returns false.
     async fn is_provider_up(&self) -> bool;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_server::serve_once;
+
+    #[test]
+    fn build_url_joins_provider_without_trailing_slash() {
+        assert_eq!(
+            build_url("https://gateway", "", "state/v2"),
+            "https://gateway/state/v2"
+        );
+    }
+
+    #[test]
+    fn build_url_joins_provider_with_trailing_slash() {
+        assert_eq!(
+            build_url("https://gateway/", "", "state/v2"),
+            "https://gateway/state/v2"
+        );
+        assert_eq!(
+            build_url("https://gateway//", "", "state/v2"),
+            "https://gateway/state/v2"
+        );
+    }
+
+    #[test]
+    fn build_url_strips_leading_slash_of_path() {
+        assert_eq!(
+            build_url("https://gateway/", "", "/state/v2"),
+            "https://gateway/state/v2"
+        );
+    }
+
+    #[test]
+    fn build_url_with_empty_base_path() {
+        assert_eq!(build_url("https://gateway", "", ""), "https://gateway/");
+        assert_eq!(build_url("https://gateway", "/", ""), "https://gateway/");
+    }
+
+    #[test]
+    fn build_url_with_base_path_slashes_on_both_ends() {
+        assert_eq!(
+            build_url("https://gateway/", "/pchain/mainnet/", "/state/v2"),
+            "https://gateway/pchain/mainnet/state/v2"
+        );
+        assert_eq!(
+            build_url("https://gateway", "pchain/mainnet", "state/v2"),
+            "https://gateway/pchain/mainnet/state/v2"
+        );
+    }
+
+    #[test]
+    fn normalize_base_path_strips_slashes() {
+        assert_eq!(
+            normalize_base_path("pchain/mainnet").unwrap(),
+            "pchain/mainnet"
+        );
+        assert_eq!(
+            normalize_base_path("pchain/mainnet/").unwrap(),
+            "pchain/mainnet"
+        );
+        assert_eq!(
+            normalize_base_path("/pchain/mainnet/").unwrap(),
+            "pchain/mainnet"
+        );
+        assert_eq!(normalize_base_path("").unwrap(), "");
+        assert_eq!(normalize_base_path("/").unwrap(), "");
+    }
+
+    #[test]
+    fn normalize_base_path_rejects_invalid_prefixes() {
+        for base_path in [
+            "pchain/../mainnet",
+            "..",
+            "pchain/./mainnet",
+            "pchain?mainnet",
+            "pchain#mainnet",
+            "pchain mainnet",
+            "pchain/\tmainnet",
+            "pchain//mainnet",
+        ] {
+            assert!(
+                normalize_base_path(base_path).is_err(),
+                "{} should be rejected",
+                base_path
+            );
+        }
+    }
+
+    #[test]
+    fn endpoint_url_uses_normalized_base_path() {
+        for base_path in ["pchain/mainnet", "pchain/mainnet/", "/pchain/mainnet/"] {
+            let mut networking = Networking::new(String::from("https://gateway/"));
+            networking.set_base_path(base_path).unwrap();
+            assert_eq!(
+                networking.endpoint_url("https://gateway/", "state/v2"),
+                "https://gateway/pchain/mainnet/state/v2"
+            );
+        }
+    }
+
+    /// `response` converts a constructed HTTP response into a reqwest::Response.
+    fn response(status: u16, headers: &[(&str, &str)], body: &'static [u8]) -> reqwest::Response {
+        let mut builder = http::Response::builder().status(status);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        reqwest::Response::from(builder.body(body).unwrap())
+    }
+
+    #[test]
+    fn retry_after_reads_delay_seconds() {
+        let response = response(503, &[("Retry-After", "120")], b"");
+        assert_eq!(retry_after(&response), Some(120));
+    }
+
+    #[test]
+    fn retry_after_is_absent_without_delay_seconds() {
+        assert_eq!(retry_after(&response(503, &[], b"")), None);
+        let response = response(
+            503,
+            &[("Retry-After", "Wed, 21 Oct 2015 07:28:00 GMT")],
+            b"",
+        );
+        assert_eq!(retry_after(&response), None);
+    }
+
+    #[tokio::test]
+    async fn read_response_maps_503_with_retry_after() {
+        let networking = Networking::new(String::from("https://gateway"));
+        let result = networking
+            .read_response(response(503, &[("Retry-After", "30")], b"maintenance"))
+            .await;
+        assert_eq!(result, Err(error::service_unavailable(Some(30))));
+        assert_eq!(
+            error::new(result.unwrap_err()),
+            error::service_unavailable(Some(30))
+        );
+        assert_eq!(networking.last_retry_after(), Some(Duration::from_secs(30)));
+
+        let result = networking.read_response(response(200, &[], b"")).await;
+        assert!(result.is_ok());
+        assert_eq!(networking.last_retry_after(), None);
+    }
+
+    #[tokio::test]
+    async fn read_response_maps_503_without_retry_after() {
+        let networking = Networking::new(String::from("https://gateway"));
+        let result = networking
+            .read_response(response(503, &[], b"maintenance"))
+            .await;
+        assert_eq!(result, Err(error::service_unavailable(None)));
+        assert_eq!(
+            error::new(result.unwrap_err()),
+            error::service_unavailable(None)
+        );
+        assert_eq!(networking.last_retry_after(), None);
+    }
+
+    #[tokio::test]
+    async fn read_response_maps_413_to_payload_too_large() {
+        let networking = Networking::new(String::from("https://gateway"));
+        let result = networking
+            .read_response(response(413, &[], b"request entity too large"))
+            .await;
+        assert_eq!(
+            error::new(result.unwrap_err()),
+            "Payload Too Large. The request exceeds the size limit of the provider."
+        );
+    }
+
+    #[test]
+    fn set_danger_accept_invalid_certs_updates_settings() {
+        let mut networking = Networking::new(String::from("https://127.0.0.1:8443"));
+        assert!(!networking.settings.accept_invalid_certs);
+
+        networking.set_danger_accept_invalid_certs(true);
+        assert!(networking.settings.accept_invalid_certs);
+
+        networking.set_danger_accept_invalid_certs(false);
+        assert!(!networking.settings.accept_invalid_certs);
+    }
+
+    fn url(url: &str) -> reqwest::Url {
+        reqwest::Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn check_redirect_refuses_https_to_http() {
+        assert_eq!(
+            check_redirect(
+                &[url("https://gateway/state/v2")],
+                &url("http://gateway/state/v2"),
+                DEFAULT_MAX_REDIRECTS
+            ),
+            Err(error::INSECURE_REDIRECT)
+        );
+    }
+
+    #[test]
+    fn check_redirect_follows_http_to_https() {
+        assert_eq!(
+            check_redirect(
+                &[url("http://gateway/state/v2")],
+                &url("https://gateway/state/v2"),
+                DEFAULT_MAX_REDIRECTS
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_redirect(
+                &[url("https://gateway/state/v2")],
+                &url("https://other-gateway/state/v2"),
+                DEFAULT_MAX_REDIRECTS
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn check_redirect_limits_redirect_count() {
+        let previous = [url("https://a/"), url("https://b/"), url("https://c/")];
+        assert_eq!(check_redirect(&previous, &url("https://d/"), 3), Ok(()));
+        assert_eq!(
+            check_redirect(&previous, &url("https://d/"), 2),
+            Err(error::TOO_MANY_REDIRECTS)
+        );
+        assert_eq!(
+            check_redirect(&previous[..1], &url("https://b/"), 0),
+            Err(error::TOO_MANY_REDIRECTS)
+        );
+    }
+
+    #[test]
+    fn set_max_in_flight_requests_rejects_zero() {
+        let mut networking = Networking::new("http://localhost".to_string());
+
+        assert!(networking.set_max_in_flight_requests(Some(0)).is_err());
+        assert!(networking.in_flight.is_none());
+        assert!(networking.set_max_in_flight_requests(Some(1)).is_ok());
+        assert!(networking.set_max_in_flight_requests(None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn raw_response_holds_in_flight_slot_until_body_is_read() {
+        let provider = serve_once(b"ok".to_vec()).await;
+        let mut networking = Networking::new(provider);
+        networking.set_max_in_flight_requests(Some(1)).unwrap();
+        let available = || networking.in_flight.as_ref().unwrap().available_permits();
+
+        let raw = networking
+            .post_raw_response("state/v2", bytes::Bytes::new())
+            .await
+            .unwrap();
+        assert_eq!(available(), 0);
+
+        assert_eq!(&raw.bytes().await.unwrap()[..], b"ok");
+        assert_eq!(available(), 1);
+    }
+
+    #[tokio::test]
+    async fn fail_fast_rejects_requests_without_connecting() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut networking = Networking::new(format!("http://{}", listener.local_addr().unwrap()));
+        networking.set_fail_fast(true);
+        networking.provider_up.store(false, Ordering::Relaxed);
+
+        let raw = networking
+            .post_raw_response("state/v2", bytes::Bytes::new())
+            .await;
+        assert_eq!(raw.unwrap_err(), error::PROVIDER_UNREACHABLE);
+        let post = networking
+            .post_response_with_headers("state/v2", bytes::Bytes::new(), HeaderMap::new())
+            .await;
+        assert_eq!(post.unwrap_err(), error::PROVIDER_UNREACHABLE);
+        let get = networking.get_response("").await;
+        assert_eq!(get.unwrap_err(), error::PROVIDER_UNREACHABLE);
+
+        // A connection attempt would be queued on the listener even though it is never accepted.
+        let accepted = tokio::time::timeout(Duration::from_millis(100), listener.accept()).await;
+        assert!(accepted.is_err());
+    }
+}
@@ -0,0 +1,76 @@
+/*
+    Copyright © 2023, ParallelChain Lab
+    Licensed under the Apache License, Version 2.0: http://www.apache.org/licenses/LICENSE-2.0
+*/
+
+//! One-shot helpers for scripts which send a single request. Each helper creates a temporary
+//! [Client](crate::Client), sends the request, and drops the Client with its connection pool.
+//! They are inefficient for repeated requests, which should share one Client instead.
+
+use pchain_types::rpc::HighestCommittedBlockResponse;
+#[cfg(feature = "v2")]
+use pchain_types::rpc::{SubmitTransactionResponseV2, TransactionV1OrV2};
+
+use crate::{error::HttpErrorResponse, Client};
+
+/// `get_highest_block` sends a request to get the latest block on ParallelChain with a
+/// temporary Client.
+/// # Arguments
+/// * `rpc_base_url` - base URL of Parallelchain RPC endpoints
+///
+pub async fn get_highest_block(
+    rpc_base_url: &str,
+) -> Result<HighestCommittedBlockResponse, HttpErrorResponse> {
+    Client::new(rpc_base_url).highest_committed_block().await
+}
+
+/// `submit_transaction` sends a request to submit a transaction using V2 RPC with a temporary
+/// Client.
+/// # Arguments
+/// * `rpc_base_url` - base URL of Parallelchain RPC endpoints
+/// * `tx` - transaction to be submitted
+///
+#[cfg(feature = "v2")]
+pub async fn submit_transaction(
+    rpc_base_url: &str,
+    tx: &TransactionV1OrV2,
+) -> Result<SubmitTransactionResponseV2, HttpErrorResponse> {
+    Client::new(rpc_base_url).submit_transaction_v2(tx).await
+}
+
+/// Blocking flavors of the one-shot helpers, for scripts without an async runtime. Each call
+/// also starts and shuts down a temporary async runtime, and must not be made from within an
+/// async runtime.
+#[cfg(feature = "blocking")]
+pub mod blocking {
+    use pchain_types::rpc::HighestCommittedBlockResponse;
+    #[cfg(feature = "v2")]
+    use pchain_types::rpc::{SubmitTransactionResponseV2, TransactionV1OrV2};
+
+    use crate::{error::HttpErrorResponse, runtime};
+
+    /// `get_highest_block` is the blocking flavor of
+    /// [get_highest_block](crate::get_highest_block).
+    /// # Arguments
+    /// * `rpc_base_url` - base URL of Parallelchain RPC endpoints
+    ///
+    pub fn get_highest_block(
+        rpc_base_url: &str,
+    ) -> Result<HighestCommittedBlockResponse, HttpErrorResponse> {
+        runtime::block_on(super::get_highest_block(rpc_base_url))?
+    }
+
+    /// `submit_transaction` is the blocking flavor of
+    /// [submit_transaction](crate::submit_transaction).
+    /// # Arguments
+    /// * `rpc_base_url` - base URL of Parallelchain RPC endpoints
+    /// * `tx` - transaction to be submitted
+    ///
+    #[cfg(feature = "v2")]
+    pub fn submit_transaction(
+        rpc_base_url: &str,
+        tx: &TransactionV1OrV2,
+    ) -> Result<SubmitTransactionResponseV2, HttpErrorResponse> {
+        runtime::block_on(super::submit_transaction(rpc_base_url, tx))?
+    }
+}
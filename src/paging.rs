@@ -0,0 +1,44 @@
+/*
+    Copyright © 2023, ParallelChain Lab
+    Licensed under the Apache License, Version 2.0: http://www.apache.org/licenses/LICENSE-2.0
+*/
+
+//! Client-side pagination over lists in responses which are already fetched, e.g. the
+//! delegators of a pool in a [ValidatorSetsResponse](pchain_types::rpc::ValidatorSetsResponse).
+//! Paging does not send any request to the network provider. The validator sets RPC has no
+//! server-side pagination parameters, so the whole set has to be fetched once.
+
+/// [Page] is a window of at most `limit` items starting at `offset` of a list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page<'a, T> {
+    /// `items` denotes the items in this page.
+    pub items: &'a [T],
+    /// `offset` denotes the index of the first item of this page in the list.
+    pub offset: usize,
+    /// `total` denotes the number of items in the list.
+    pub total: usize,
+}
+
+impl<'a, T> Page<'a, T> {
+    /// `has_next` returns true if there are items in the list after this page.
+    pub fn has_next(&self) -> bool {
+        self.offset + self.items.len() < self.total
+    }
+}
+
+/// `page` returns the page of at most `limit` items starting at `offset`. The page is empty if
+/// `offset` is beyond the end of the list.
+/// # Arguments
+/// * `items` - list to be paged, e.g. the delegated stakes of a pool
+/// * `offset` - index of the first item of the page
+/// * `limit` - maximum number of items in the page
+///
+pub fn page<T>(items: &[T], offset: usize, limit: usize) -> Page<'_, T> {
+    let start = offset.min(items.len());
+    let end = start.saturating_add(limit).min(items.len());
+    Page {
+        items: &items[start..end],
+        offset: start,
+        total: items.len(),
+    }
+}
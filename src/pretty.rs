@@ -0,0 +1,88 @@
+/*
+    Copyright © 2023, ParallelChain Lab
+    Licensed under the Apache License, Version 2.0: http://www.apache.org/licenses/LICENSE-2.0
+*/
+
+//! Human-readable formatting of RPC responses, enabled by the `pretty` feature.
+
+use std::fmt;
+
+use pchain_types::{
+    blockchain::{CommandReceiptV2, ExitCodeV2},
+    rpc::ReceiptResponseV2,
+};
+
+/// [Pretty] wraps a response type and implements a human-readable [fmt::Display] for it.
+pub struct Pretty<T>(pub T);
+
+impl fmt::Display for Pretty<ReceiptResponseV2> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let response = &self.0;
+        writeln!(
+            f,
+            "Transaction Hash: {}",
+            to_hex(&response.transaction_hash)
+        )?;
+        match &response.block_hash {
+            Some(block_hash) => writeln!(f, "Block Hash: {}", to_hex(block_hash))?,
+            None => writeln!(f, "Block Hash: -")?,
+        }
+        match response.position {
+            Some(position) => writeln!(f, "Position: {}", position)?,
+            None => writeln!(f, "Position: -")?,
+        }
+        let receipt = match &response.receipt {
+            Some(receipt) => receipt,
+            None => return write!(f, "Receipt: not found"),
+        };
+        writeln!(f, "Status: {}", exit_code_name(&receipt.exit_code))?;
+        write!(f, "Gas Used: {}", receipt.gas_used)?;
+        for (index, command_receipt) in receipt.command_receipts.iter().enumerate() {
+            let (exit_code, gas_used, return_value_len) = command_receipt_summary(command_receipt);
+            write!(
+                f,
+                "\nCommand {}: {}, Gas Used: {}, Return Value: {} bytes",
+                index,
+                exit_code_name(exit_code),
+                gas_used,
+                return_value_len
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// `exit_code_name` names an exit code for display.
+fn exit_code_name(exit_code: &ExitCodeV2) -> &'static str {
+    match exit_code {
+        ExitCodeV2::Ok => "Ok",
+        ExitCodeV2::Error => "Error",
+        ExitCodeV2::GasExhausted => "Gas Exhausted",
+    }
+}
+
+/// `command_receipt_summary` extracts the exit code, the gas used and the length of the return
+/// value of a command receipt. Only Call commands return a value, so the length is 0 for the
+/// other commands.
+fn command_receipt_summary(command_receipt: &CommandReceiptV2) -> (&ExitCodeV2, u64, usize) {
+    match command_receipt {
+        CommandReceiptV2::Transfer(r) => (&r.exit_code, r.gas_used, 0),
+        CommandReceiptV2::Call(r) => (&r.exit_code, r.gas_used, r.return_value.len()),
+        CommandReceiptV2::Deploy(r) => (&r.exit_code, r.gas_used, 0),
+        CommandReceiptV2::CreatePool(r) => (&r.exit_code, r.gas_used, 0),
+        CommandReceiptV2::SetPoolSettings(r) => (&r.exit_code, r.gas_used, 0),
+        CommandReceiptV2::DeletePool(r) => (&r.exit_code, r.gas_used, 0),
+        CommandReceiptV2::CreateDeposit(r) => (&r.exit_code, r.gas_used, 0),
+        CommandReceiptV2::SetDepositSettings(r) => (&r.exit_code, r.gas_used, 0),
+        CommandReceiptV2::TopUpDeposit(r) => (&r.exit_code, r.gas_used, 0),
+        CommandReceiptV2::WithdrawDeposit(r) => (&r.exit_code, r.gas_used, 0),
+        CommandReceiptV2::StakeDeposit(r) => (&r.exit_code, r.gas_used, 0),
+        CommandReceiptV2::UnstakeDeposit(r) => (&r.exit_code, r.gas_used, 0),
+        CommandReceiptV2::NextEpoch(r) => (&r.exit_code, r.gas_used, 0),
+    }
+}
+
+/// `to_hex` encodes bytes as a lowercase hex string.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,42 @@
+/*
+    Copyright © 2023, ParallelChain Lab
+    Licensed under the Apache License, Version 2.0: http://www.apache.org/licenses/LICENSE-2.0
+*/
+
+//! Async runtime specific calls (sleep, timeout, spawn, block_on) used by this library. reqwest
+//! requires a tokio 1 runtime, so these calls use tokio 1 as well. Keeping them in this module
+//! isolates the runtime from the rest of the library.
+
+use std::{future::Future, time::Duration};
+
+/// `sleep` waits until `duration` has elapsed.
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+/// `timeout` awaits `future` for at most `duration`.
+///
+/// # Return
+/// Output of the future, or Err if `duration` elapsed first.
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, ()> {
+    tokio::time::timeout(duration, future).await.map_err(|_| ())
+}
+
+/// `block_on` runs `future` to completion on a temporary current-thread runtime, blocking the
+/// current thread. Must not be called from within an async runtime.
+///
+/// # Return
+/// Output of the future, or Err if the runtime cannot be started.
+#[cfg(feature = "blocking")]
+pub(crate) fn block_on<F: Future>(future: F) -> Result<F::Output, String> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("Async runtime cannot be started: {}", e))?;
+    Ok(runtime.block_on(future))
+}
+
+/// `spawn` runs `future` as a background task.
+pub(crate) fn spawn<F: Future<Output = ()> + Send + 'static>(future: F) {
+    tokio::spawn(future);
+}
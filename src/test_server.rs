@@ -0,0 +1,83 @@
+/*
+    Copyright © 2023, ParallelChain Lab
+    Licensed under the Apache License, Version 2.0: http://www.apache.org/licenses/LICENSE-2.0
+*/
+
+//! Local HTTP servers which answer every request with a fixed body, for unit tests.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+/// `serve_once` starts a local HTTP server which answers a single request with `body`.
+///
+/// # Return
+/// Base URL of the server.
+pub(crate) async fn serve_once(body: Vec<u8>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        answer(stream, &body).await;
+    });
+
+    url
+}
+
+/// `serve` starts a local HTTP server which answers every request with `body`.
+///
+/// # Return
+/// Base URL of the server, and the number of requests received so far.
+pub(crate) async fn serve(body: Vec<u8>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+
+    let received = requests.clone();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            received.fetch_add(1, Ordering::SeqCst);
+            answer(stream, &body).await;
+        }
+    });
+
+    (url, requests)
+}
+
+/// `answer` reads a request and answers it with `body`, closing the connection afterwards.
+async fn answer(mut stream: TcpStream, body: &[u8]) {
+    // Read the request head and as much of the body as its Content-Length declares.
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        let n = stream.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&request).to_lowercase();
+        if let Some(head_len) = text.find("\r\n\r\n") {
+            let content_length = text[..head_len]
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .map_or(0, |len| len.trim().parse::<usize>().unwrap());
+            if n == 0 || request.len() >= head_len + 4 + content_length {
+                break;
+            }
+        } else if n == 0 {
+            break;
+        }
+    }
+
+    let head = format!(
+        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await.unwrap();
+    stream.write_all(body).await.unwrap();
+}
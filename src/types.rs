@@ -0,0 +1,96 @@
+/*
+    Copyright © 2023, ParallelChain Lab
+    Licensed under the Apache License, Version 2.0: http://www.apache.org/licenses/LICENSE-2.0
+*/
+
+//! Convenience types returned by [Client](crate::Client) methods which extract data from one or
+//! more RPC responses, or passed to them.
+
+use std::marker::PhantomData;
+
+use pchain_types::{cryptography::CryptoHash, rpc::TransactionV1OrV2};
+
+use crate::networking::Encoding;
+
+/// [ChainInfo] identifies the chain which a network provider is serving.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainInfo {
+    /// `chain_id` denotes the chain ID in the genesis block header.
+    pub chain_id: u64,
+    /// `genesis_hash` denotes the hash of the genesis block.
+    pub genesis_hash: CryptoHash,
+}
+
+/// [GasParams] houses the gas parameters of the highest committed block, as a reference for
+/// setting the gas fields of new transactions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GasParams {
+    /// `block_hash` denotes the hash of the block which the parameters are read from.
+    pub block_hash: CryptoHash,
+    /// `base_fee_per_gas` denotes the base fee per gas of the block.
+    pub base_fee_per_gas: u64,
+    /// `gas_used` denotes the total gas used by the transactions in the block.
+    pub gas_used: u64,
+}
+
+/// [ContractInfo] houses the account data of a contract, read from a single state response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractInfo {
+    /// `balance` denotes the balance of the contract account.
+    pub balance: u64,
+    /// `nonce` denotes the nonce of the contract account.
+    pub nonce: u64,
+    /// `cbi_version` denotes the CBI version of the contract, if reported by the network provider.
+    pub cbi_version: Option<u32>,
+    /// `storage_hash` denotes the root hash of the contract storage, if reported by the network
+    /// provider.
+    pub storage_hash: Option<CryptoHash>,
+    /// `contract` denotes the contract code (WASM bytecode).
+    pub contract: Vec<u8>,
+}
+
+/// [TransactionsPage] is a page of transactions which involve an address, returned by
+/// [Client::transactions_by_address_v2](crate::Client::transactions_by_address_v2). The next
+/// page is fetched by resuming the scan from `next_height` and `next_offset`.
+#[derive(Clone, Debug)]
+pub struct TransactionsPage {
+    /// `items` denotes the matching transactions in this page, in block order.
+    pub items: Vec<TransactionV1OrV2>,
+    /// `next_height` denotes the block height which the next page starts scanning from.
+    pub next_height: u64,
+    /// `next_offset` denotes the number of matching transactions in the block at `next_height`
+    /// which are already returned and skipped by the next page.
+    pub next_offset: usize,
+    /// `has_more` denotes whether the scan stopped before the end of the range, so that the
+    /// next page may contain more matching transactions.
+    pub has_more: bool,
+}
+
+/// [PreparedRequest] is a request of type `I` which is serialized once by
+/// [Client::prepare](crate::Client::prepare) and can be sent repeatedly without being
+/// serialized again. It can only be sent by a Client with the same encoding.
+pub struct PreparedRequest<I> {
+    /// `data` denotes the serialized request. Cloning it only increments a reference count, so
+    /// the request is sent repeatedly without being copied.
+    pub(crate) data: bytes::Bytes,
+    /// `encoding` denotes the encoding which the request is serialized with.
+    pub(crate) encoding: Encoding,
+    _request: PhantomData<fn(&I)>,
+}
+
+impl<I> PreparedRequest<I> {
+    /// `new` wraps a serialized request.
+    pub(crate) fn new(data: bytes::Bytes, encoding: Encoding) -> Self {
+        Self {
+            data,
+            encoding,
+            _request: PhantomData,
+        }
+    }
+}
+
+impl<I> Clone for PreparedRequest<I> {
+    fn clone(&self) -> Self {
+        Self::new(self.data.clone(), self.encoding)
+    }
+}
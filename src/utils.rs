@@ -0,0 +1,330 @@
+/*
+    Copyright © 2023, ParallelChain Lab
+    Licensed under the Apache License, Version 2.0: http://www.apache.org/licenses/LICENSE-2.0
+*/
+
+//! Helpers for working with the data in requests and responses without sending any request.
+
+use std::{collections::BTreeMap, convert::TryInto, fmt, str::FromStr};
+
+use base64::{
+    engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use borsh::BorshDeserialize;
+use pchain_types::{
+    blockchain::{Command, TransactionV1, TransactionV2},
+    cryptography::PublicAddress,
+    rpc::{BlockRequest, ReceiptRequest, TransactionRequest, TransactionV1OrV2, ValidatorSet},
+    serialization::Serializable,
+};
+
+/// `split_transactions` separates transactions into V1 and V2 transactions, preserving their
+/// order.
+/// # Arguments
+/// * `transactions` - transactions, e.g. returned by `block_transactions_v2`
+///
+pub fn split_transactions(
+    transactions: Vec<TransactionV1OrV2>,
+) -> (Vec<TransactionV1>, Vec<TransactionV2>) {
+    let mut v1_transactions = Vec::new();
+    let mut v2_transactions = Vec::new();
+    for transaction in transactions {
+        match transaction {
+            TransactionV1OrV2::V1(tx) => v1_transactions.push(tx),
+            TransactionV1OrV2::V2(tx) => v2_transactions.push(tx),
+        }
+    }
+    (v1_transactions, v2_transactions)
+}
+
+/// `involves_address` returns true if a transaction involves an address, i.e. the address is
+/// the signer of the transaction, the recipient of a Transfer command, or the target of a Call
+/// command. Calls made internally by contracts during execution are not visible in the
+/// transaction and hence not matched.
+/// # Arguments
+/// * `transaction` - transaction to be checked
+/// * `address` - address to be matched
+///
+pub fn involves_address(transaction: &TransactionV1OrV2, address: &PublicAddress) -> bool {
+    let (signer, commands) = match transaction {
+        TransactionV1OrV2::V1(tx) => (&tx.signer, &tx.commands),
+        TransactionV1OrV2::V2(tx) => (&tx.signer, &tx.commands),
+    };
+
+    signer == address
+        || commands.iter().any(|command| match command {
+            Command::Transfer(input) => &input.recipient == address,
+            Command::Call(input) => &input.target == address,
+            _ => false,
+        })
+}
+
+/// `transaction_size` returns the size in bytes of a serialized transaction, e.g. to check it
+/// against the size limit of the provider before submitting it.
+/// # Arguments
+/// * `tx` - transaction to be submitted
+///
+pub fn transaction_size(tx: &TransactionV1OrV2) -> usize {
+    <TransactionV1OrV2 as Serializable>::serialize(tx).len()
+}
+
+/// `decode_storage_map` decodes raw key-value pairs of contract storage into a typed map, e.g.
+/// the storage of a contract which keeps a logical `HashMap<String, u64>`. Keys and values are
+/// decoded with Borsh.
+/// # Arguments
+/// * `entries` - raw key-value pairs of contract storage
+///
+/// # Return
+/// Error message naming the first entry whose key or value fails to decode.
+///
+pub fn decode_storage_map<K, V, I>(entries: I) -> Result<BTreeMap<K, V>, String>
+where
+    K: BorshDeserialize + Ord,
+    V: BorshDeserialize,
+    I: IntoIterator<Item = (Vec<u8>, Vec<u8>)>,
+{
+    entries
+        .into_iter()
+        .map(|(key, value)| {
+            let decoded_key = K::try_from_slice(&key).map_err(|e| {
+                format!("Storage key {} fails to decode: {}", key.to_base64url(), e)
+            })?;
+            let decoded_value = V::try_from_slice(&value).map_err(|e| {
+                format!(
+                    "Storage value of key {} fails to decode: {}",
+                    key.to_base64url(),
+                    e
+                )
+            })?;
+            Ok((decoded_key, decoded_value))
+        })
+        .collect()
+}
+
+/// [ValidatorSetDiff] houses the changes between two validator sets, each list ordered by
+/// operator address.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidatorSetDiff {
+    /// `added` denotes the operators and powers of the pools which only the later set contains.
+    pub added: Vec<(PublicAddress, u64)>,
+    /// `removed` denotes the operators and powers of the pools which only the earlier set
+    /// contains.
+    pub removed: Vec<(PublicAddress, u64)>,
+    /// `changed` denotes the pools which both sets contain with different powers.
+    pub changed: Vec<PowerChange>,
+}
+
+/// [PowerChange] denotes the change of power of a pool between two validator sets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PowerChange {
+    /// `operator` denotes the operator address of the pool.
+    pub operator: PublicAddress,
+    /// `before` denotes the power of the pool in the earlier set.
+    pub before: u64,
+    /// `after` denotes the power of the pool in the later set.
+    pub after: u64,
+}
+
+/// `diff_validator_sets` compares two validator sets, e.g. the current validator sets of two
+/// `ValidatorSetsResponse` snapshots, keyed by the operator address of each pool. Sets with and
+/// without delegators can be compared with each other.
+/// # Arguments
+/// * `prev` - earlier validator set
+/// * `next` - later validator set
+///
+pub fn diff_validator_sets(prev: &ValidatorSet, next: &ValidatorSet) -> ValidatorSetDiff {
+    let prev = pool_powers(prev);
+    let mut next = pool_powers(next);
+
+    let mut diff = ValidatorSetDiff::default();
+    for (operator, before) in prev {
+        match next.remove(&operator) {
+            Some(after) if after != before => diff.changed.push(PowerChange {
+                operator,
+                before,
+                after,
+            }),
+            Some(_) => {}
+            None => diff.removed.push((operator, before)),
+        }
+    }
+    diff.added = next.into_iter().collect();
+    diff
+}
+
+/// `pool_powers` maps the operator address of each pool in a validator set to its power.
+fn pool_powers(validator_set: &ValidatorSet) -> BTreeMap<PublicAddress, u64> {
+    match validator_set {
+        ValidatorSet::WithDelegators(pools) => pools
+            .iter()
+            .map(|pool| (pool.operator, pool.power))
+            .collect(),
+        ValidatorSet::WithoutDelegators(pools) => pools
+            .iter()
+            .map(|pool| (pool.operator, pool.power))
+            .collect(),
+    }
+}
+
+/// [ToBase64UrlDisplay] renders bytes, e.g. hashes and addresses in responses, as the canonical
+/// Base64URL (no padding) strings used by the ParallelChain explorer.
+pub trait ToBase64UrlDisplay {
+    /// `to_base64url` encodes the bytes as a Base64URL string without padding.
+    fn to_base64url(&self) -> String;
+}
+
+impl ToBase64UrlDisplay for [u8] {
+    fn to_base64url(&self) -> String {
+        URL_SAFE_NO_PAD.encode(self)
+    }
+}
+
+/// `base64_to_bytes` decodes a Base64URL or standard Base64 string, with or without padding.
+/// The alphabet is detected from the string.
+/// # Arguments
+/// * `input` - Base64URL or standard Base64 string
+///
+pub fn base64_to_bytes(input: &str) -> Result<Vec<u8>, String> {
+    let unpadded = input.trim_end_matches('=');
+    let engine = if unpadded.contains('+') || unpadded.contains('/') {
+        STANDARD_NO_PAD
+    } else {
+        URL_SAFE_NO_PAD
+    };
+    engine
+        .decode(unpadded)
+        .map_err(|e| format!("Invalid Base64 string: {}", e))
+}
+
+/// `base64_to_bytes32` decodes a Base64URL or standard Base64 string into 32 bytes, e.g. a
+/// hash or an address.
+/// # Arguments
+/// * `input` - Base64URL or standard Base64 string
+///
+pub fn base64_to_bytes32(input: &str) -> Result<[u8; 32], String> {
+    let bytes = base64_to_bytes(input)?;
+    let len = bytes.len();
+    bytes
+        .try_into()
+        .map_err(|_| format!("Expected 32 bytes, but decoded {} bytes.", len))
+}
+
+/// [Base64UrlHash] is a 32-byte hash or address which is parsed from and displayed as a
+/// Base64URL string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Base64UrlHash(pub [u8; 32]);
+
+impl FromStr for Base64UrlHash {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        base64_to_bytes32(s).map(Base64UrlHash)
+    }
+}
+
+impl fmt::Display for Base64UrlHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.to_base64url())
+    }
+}
+
+impl From<[u8; 32]> for Base64UrlHash {
+    fn from(bytes: [u8; 32]) -> Self {
+        Base64UrlHash(bytes)
+    }
+}
+
+impl From<Base64UrlHash> for [u8; 32] {
+    fn from(hash: Base64UrlHash) -> Self {
+        hash.0
+    }
+}
+
+/// `block_request_from_base64url` creates a [BlockRequest] from a Base64URL block hash, e.g.
+/// copied from the explorer.
+/// # Arguments
+/// * `block_hash` - Base64URL encoded block hash
+///
+pub fn block_request_from_base64url(block_hash: &str) -> Result<BlockRequest, String> {
+    Ok(BlockRequest {
+        block_hash: block_hash
+            .parse::<Base64UrlHash>()
+            .map_err(|e| format!("Invalid block hash. {}", e))?
+            .into(),
+    })
+}
+
+/// `transaction_request_from_base64url` creates a [TransactionRequest] from a Base64URL
+/// transaction hash, e.g. copied from the explorer.
+/// # Arguments
+/// * `transaction_hash` - Base64URL encoded transaction hash
+/// * `include_receipt` - whether the receipt is included in the response
+///
+pub fn transaction_request_from_base64url(
+    transaction_hash: &str,
+    include_receipt: bool,
+) -> Result<TransactionRequest, String> {
+    Ok(TransactionRequest {
+        transaction_hash: transaction_hash
+            .parse::<Base64UrlHash>()
+            .map_err(|e| format!("Invalid transaction hash. {}", e))?
+            .into(),
+        include_receipt,
+    })
+}
+
+/// `receipt_request_from_base64url` creates a [ReceiptRequest] from a Base64URL transaction
+/// hash, e.g. copied from the explorer.
+/// # Arguments
+/// * `transaction_hash` - Base64URL encoded transaction hash
+///
+pub fn receipt_request_from_base64url(transaction_hash: &str) -> Result<ReceiptRequest, String> {
+    Ok(ReceiptRequest {
+        transaction_hash: transaction_hash
+            .parse::<Base64UrlHash>()
+            .map_err(|e| format!("Invalid transaction hash. {}", e))?
+            .into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pchain_types::rpc::PoolWithoutDelegators;
+
+    fn pool(operator: u8, power: u64) -> PoolWithoutDelegators {
+        PoolWithoutDelegators {
+            operator: [operator; 32],
+            power,
+            commission_rate: 5,
+            operator_stake: None,
+        }
+    }
+
+    #[test]
+    fn diff_validator_sets_reports_added_removed_and_changed_pools() {
+        let prev = ValidatorSet::WithoutDelegators(vec![pool(1, 100), pool(2, 200), pool(3, 300)]);
+        let next = ValidatorSet::WithoutDelegators(vec![pool(4, 400), pool(2, 250), pool(3, 300)]);
+
+        assert_eq!(
+            diff_validator_sets(&prev, &next),
+            ValidatorSetDiff {
+                added: vec![([4; 32], 400)],
+                removed: vec![([1; 32], 100)],
+                changed: vec![PowerChange {
+                    operator: [2; 32],
+                    before: 200,
+                    after: 250,
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn diff_validator_sets_of_identical_sets_is_empty() {
+        let set = ValidatorSet::WithoutDelegators(vec![pool(1, 100), pool(2, 200)]);
+        assert_eq!(diff_validator_sets(&set, &set), ValidatorSetDiff::default());
+    }
+}
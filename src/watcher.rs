@@ -0,0 +1,321 @@
+/*
+    Copyright © 2023, ParallelChain Lab
+    Licensed under the Apache License, Version 2.0: http://www.apache.org/licenses/LICENSE-2.0
+*/
+
+//! Shared polling for transaction receipts. Waiters on the same transaction hash share a single
+//! polling loop against the network provider.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use futures::future::{AbortHandle, Abortable};
+
+use pchain_types::{
+    cryptography::CryptoHash,
+    rpc::{ReceiptRequest, ReceiptResponseV2},
+};
+use tokio::sync::Notify;
+
+use crate::{error::HttpErrorResponse, runtime, Client};
+
+/// [ReceiptWatcher] dedupes receipt polling per transaction hash. The first call to
+/// [watch](ReceiptWatcher::watch) for a hash starts a polling loop with `receipt_v2`, later
+/// calls for the same hash join the existing loop.
+pub struct ReceiptWatcher {
+    /// `client` denotes the Client used for polling.
+    client: Arc<Client>,
+    /// `poll_interval` denotes the delay between two polls of the same transaction hash.
+    poll_interval: Duration,
+    /// `watches` denotes the polling loops which are currently running.
+    watches: Arc<Mutex<HashMap<CryptoHash, Arc<Watch>>>>,
+    /// `shutdown` denotes the shutdown state shared with the polling loops.
+    shutdown: Arc<Shutdown>,
+}
+
+/// [Shutdown] is the shutdown state shared between a [ReceiptWatcher] and its polling loops.
+#[derive(Default)]
+struct Shutdown {
+    /// `requested` is set once by [shutdown](ReceiptWatcher::shutdown).
+    requested: AtomicBool,
+    /// `finished` wakes [shutdown](ReceiptWatcher::shutdown) whenever a polling loop finishes.
+    finished: Notify,
+}
+
+/// [Watch] is the state shared between a polling loop and its handles.
+#[derive(Default)]
+struct Watch {
+    /// `result` is set once by the polling loop when it finishes.
+    result: Mutex<Option<Result<ReceiptResponseV2, HttpErrorResponse>>>,
+    /// `notify` wakes the handles once `result` is set.
+    notify: Notify,
+    /// `abort_handle` stops the polling loop on [shutdown](ReceiptWatcher::shutdown).
+    abort_handle: Option<AbortHandle>,
+    /// `handles` denotes the number of live [ReceiptHandle]s of this watch.
+    handles: AtomicUsize,
+}
+
+/// [ReceiptHandle] is an awaitable handle on the receipt of a watched transaction.
+/// Dropping every handle of a transaction hash cancels its polling loop.
+pub struct ReceiptHandle {
+    watch: Arc<Watch>,
+}
+
+impl ReceiptWatcher {
+    /// `new` creates a new ReceiptWatcher.
+    /// # Arguments
+    /// * `client` - Client used for polling
+    /// * `poll_interval` - delay between two polls of the same transaction hash
+    ///
+    pub fn new(client: Arc<Client>, poll_interval: Duration) -> Self {
+        Self {
+            client,
+            poll_interval,
+            watches: Arc::new(Mutex::new(HashMap::new())),
+            shutdown: Arc::new(Shutdown::default()),
+        }
+    }
+
+    /// `watch` returns a handle on the receipt of a transaction, starting a polling loop if no
+    /// loop is running for this transaction hash. Must be called within the async runtime.
+    /// After [shutdown](ReceiptWatcher::shutdown), the returned handle fails immediately.
+    /// # Arguments
+    /// * `transaction_hash` - hash of the transaction to watch
+    ///
+    pub fn watch(&self, transaction_hash: CryptoHash) -> ReceiptHandle {
+        let mut watches = self.watches.lock().unwrap();
+        if self.shutdown.requested.load(Ordering::SeqCst) {
+            let watch = Watch::default();
+            *watch.result.lock().unwrap() = Some(Err(String::from(SHUT_DOWN)));
+            return ReceiptHandle::new(Arc::new(watch));
+        }
+        if let Some(watch) = watches.get(&transaction_hash) {
+            return ReceiptHandle::new(watch.clone());
+        }
+
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let watch = Arc::new(Watch {
+            abort_handle: Some(abort_handle),
+            ..Watch::default()
+        });
+        watches.insert(transaction_hash, watch.clone());
+        let handle = ReceiptHandle::new(watch.clone());
+
+        let poll = Abortable::new(
+            poll_receipt(
+                self.client.clone(),
+                transaction_hash,
+                self.poll_interval,
+                watch.clone(),
+                self.watches.clone(),
+            ),
+            abort_registration,
+        );
+        let task_watch = watch.clone();
+        let watches = self.watches.clone();
+        let shutdown = self.shutdown.clone();
+        runtime::spawn(async move {
+            if poll.await.is_err() {
+                finish(&task_watch, Err(String::from(SHUT_DOWN)));
+                watches.lock().unwrap().remove(&transaction_hash);
+            }
+            shutdown.finished.notify_one();
+        });
+
+        handle
+    }
+
+    /// `shutdown` stops all polling loops and waits until they have finished. Requests in
+    /// flight are abandoned, and handles of unfinished transactions fail with an error. Later
+    /// calls to [watch](ReceiptWatcher::watch) return handles which fail immediately. To cancel
+    /// the polling loop of a single transaction instead, drop all of its handles.
+    pub async fn shutdown(&self) {
+        let watches: Vec<Arc<Watch>> = {
+            let watches = self.watches.lock().unwrap();
+            self.shutdown.requested.store(true, Ordering::SeqCst);
+            watches.values().cloned().collect()
+        };
+        for watch in watches {
+            if let Some(abort_handle) = &watch.abort_handle {
+                abort_handle.abort();
+            }
+        }
+
+        while !self.watches.lock().unwrap().is_empty() {
+            self.shutdown.finished.notified().await;
+        }
+    }
+}
+
+impl ReceiptHandle {
+    /// `new` creates a handle on a watch. Callers which share the watch through `watches` must
+    /// hold its lock, so that a polling loop cannot find zero handles in the meantime.
+    fn new(watch: Arc<Watch>) -> Self {
+        watch.handles.fetch_add(1, Ordering::SeqCst);
+        Self { watch }
+    }
+
+    /// `wait` waits until the watched transaction has a receipt.
+    ///
+    /// # Return
+    /// The receipt response, or the error which stopped the polling loop.
+    pub async fn wait(&self) -> Result<ReceiptResponseV2, HttpErrorResponse> {
+        loop {
+            let result = self.watch.result.lock().unwrap().clone();
+            if let Some(result) = result {
+                // Pass the wake-up on to the next handle waiting on the same watch.
+                self.watch.notify.notify_one();
+                return result;
+            }
+            self.watch.notify.notified().await;
+        }
+    }
+}
+
+impl Drop for ReceiptHandle {
+    fn drop(&mut self) {
+        self.watch.handles.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Error message of handles whose polling loop is stopped by [ReceiptWatcher::shutdown].
+const SHUT_DOWN: &str = "Receipt watcher is shut down.";
+
+/// `finish` sets the result of a watch and wakes its handles.
+fn finish(watch: &Watch, result: Result<ReceiptResponseV2, HttpErrorResponse>) {
+    *watch.result.lock().unwrap() = Some(result);
+    watch.notify.notify_one();
+}
+
+/// `poll_receipt` polls `receipt_v2` until the receipt is found, an error occurs, or all
+/// handles of the watch are dropped.
+async fn poll_receipt(
+    client: Arc<Client>,
+    transaction_hash: CryptoHash,
+    poll_interval: Duration,
+    watch: Arc<Watch>,
+    watches: Arc<Mutex<HashMap<CryptoHash, Arc<Watch>>>>,
+) {
+    let request = ReceiptRequest { transaction_hash };
+
+    let result = loop {
+        {
+            // Every handle is dropped, so nobody is waiting anymore. The lock prevents `watch`
+            // from handing out a new handle in the meantime.
+            let mut watches = watches.lock().unwrap();
+            if watch.handles.load(Ordering::SeqCst) == 0 {
+                watches.remove(&transaction_hash);
+                return;
+            }
+        }
+
+        match client.receipt_v2(&request).await {
+            Ok(response) if response.receipt.is_some() => break Ok(response),
+            Ok(_) => runtime::sleep(poll_interval).await,
+            Err(e) => break Err(e),
+        }
+    };
+
+    finish(&watch, result);
+    watches.lock().unwrap().remove(&transaction_hash);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pchain_types::{
+        blockchain::{ExitCodeV2, ReceiptV2},
+        serialization::Serializable,
+    };
+
+    use crate::test_server::serve;
+
+    const TRANSACTION_HASH: CryptoHash = [1u8; 32];
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    fn receipt_response(found: bool) -> Vec<u8> {
+        ReceiptResponseV2 {
+            transaction_hash: TRANSACTION_HASH,
+            receipt: found.then_some(ReceiptV2 {
+                gas_used: 0,
+                exit_code: ExitCodeV2::Ok,
+                command_receipts: Vec::new(),
+            }),
+            block_hash: found.then_some([3u8; 32]),
+            position: found.then_some(0),
+        }
+        .serialize()
+    }
+
+    #[tokio::test]
+    async fn watches_of_one_hash_share_one_polling_loop() {
+        let (url, requests) = serve(receipt_response(true)).await;
+        let watcher = ReceiptWatcher::new(Arc::new(Client::new(&url)), POLL_INTERVAL);
+
+        let handles: Vec<ReceiptHandle> = (0..5).map(|_| watcher.watch(TRANSACTION_HASH)).collect();
+        assert_eq!(watcher.watches.lock().unwrap().len(), 1);
+
+        for handle in &handles {
+            assert!(handle.wait().await.unwrap().receipt.is_some());
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn dropping_every_handle_stops_polling_loop() {
+        let (url, requests) = serve(receipt_response(false)).await;
+        let watcher = ReceiptWatcher::new(Arc::new(Client::new(&url)), POLL_INTERVAL);
+
+        let handles = vec![
+            watcher.watch(TRANSACTION_HASH),
+            watcher.watch(TRANSACTION_HASH),
+        ];
+        while requests.load(Ordering::SeqCst) == 0 {
+            runtime::sleep(POLL_INTERVAL).await;
+        }
+        drop(handles);
+
+        runtime::timeout(Duration::from_secs(5), async {
+            while !watcher.watches.lock().unwrap().is_empty() {
+                runtime::sleep(POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .expect("Polling loop is not stopped");
+
+        let polled = requests.load(Ordering::SeqCst);
+        runtime::sleep(POLL_INTERVAL * 5).await;
+        assert_eq!(requests.load(Ordering::SeqCst), polled);
+    }
+
+    #[tokio::test]
+    async fn shutdown_stops_polling_and_fails_handles() {
+        let (url, requests) = serve(receipt_response(false)).await;
+        let watcher = ReceiptWatcher::new(Arc::new(Client::new(&url)), POLL_INTERVAL);
+
+        let handle = watcher.watch(TRANSACTION_HASH);
+        while requests.load(Ordering::SeqCst) == 0 {
+            runtime::sleep(POLL_INTERVAL).await;
+        }
+
+        runtime::timeout(Duration::from_secs(5), watcher.shutdown())
+            .await
+            .expect("Shutdown does not return");
+        assert!(watcher.watches.lock().unwrap().is_empty());
+        assert_eq!(handle.wait().await.unwrap_err(), SHUT_DOWN);
+
+        let result = runtime::timeout(Duration::from_secs(5), async {
+            watcher.watch(TRANSACTION_HASH).wait().await
+        })
+        .await
+        .expect("Watch after shutdown does not fail immediately");
+        assert_eq!(result.unwrap_err(), SHUT_DOWN);
+    }
+}